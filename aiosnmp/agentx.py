@@ -0,0 +1,559 @@
+__all__ = (
+    "AgentXType",
+    "AgentXFlags",
+    "AgentXError",
+    "CloseReason",
+    "AgentXHeader",
+    "SearchRange",
+    "AgentXPDU",
+    "AgentXOpen",
+    "AgentXClose",
+    "AgentXRegister",
+    "AgentXUnregister",
+    "AgentXGet",
+    "AgentXGetNext",
+    "AgentXGetBulk",
+    "AgentXTestSet",
+    "AgentXCommitSet",
+    "AgentXUndoSet",
+    "AgentXCleanupSet",
+    "AgentXNotify",
+    "AgentXPing",
+    "AgentXResponse",
+    "HEADER_SIZE",
+    "decode_header",
+    "decode_pdu",
+    "encode_pdu",
+    "read_pdu",
+)
+
+import asyncio
+import enum
+import ipaddress
+import struct
+from typing import Dict, List, NamedTuple, Optional, Tuple, Type, Union
+
+from .asn1 import Number
+from .message import SnmpVarbind
+
+HEADER_SIZE = 20
+_VERSION = 1
+# 1.3.6.1.<prefix> is sent as a single octet
+_INTERNET_PREFIX = (1, 3, 6, 1)
+
+
+class AgentXType(enum.IntEnum):
+    Open = 1
+    Close = 2
+    Register = 3
+    Unregister = 4
+    Get = 5
+    GetNext = 6
+    GetBulk = 7
+    TestSet = 8
+    CommitSet = 9
+    UndoSet = 10
+    CleanupSet = 11
+    Notify = 12
+    Ping = 13
+    IndexAllocate = 14
+    IndexDeallocate = 15
+    AddAgentCaps = 16
+    RemoveAgentCaps = 17
+    Response = 18
+
+
+class AgentXFlags(enum.IntFlag):
+    InstanceRegistration = 0x01
+    NewIndex = 0x02
+    AnyIndex = 0x04
+    NonDefaultContext = 0x08
+    NetworkByteOrder = 0x10
+
+
+class AgentXError(enum.IntEnum):
+    # values below 256 are the SNMP error-status values
+    NoAgentXError = 0
+    OpenFailed = 256
+    NotOpen = 257
+    IndexWrongType = 258
+    IndexAlreadyAllocated = 259
+    IndexNoneAvailable = 260
+    IndexNotAllocated = 261
+    UnsupportedContext = 262
+    DuplicateRegistration = 263
+    UnknownRegistration = 264
+    UnknownAgentCaps = 265
+    ParseError = 266
+    RequestDenied = 267
+    ProcessingError = 268
+
+
+class CloseReason(enum.IntEnum):
+    Other = 1
+    ParseError = 2
+    ProtocolError = 3
+    Timeouts = 4
+    Shutdown = 5
+    ByManager = 6
+
+
+class AgentXHeader(NamedTuple):
+    pdu_type: int
+    flags: int
+    session_id: int
+    transaction_id: int
+    packet_id: int
+    payload_length: int
+
+    @property
+    def endian(self) -> str:
+        return "!" if self.flags & AgentXFlags.NetworkByteOrder else "<"
+
+
+class SearchRange(NamedTuple):
+    start: str
+    # the null OID "" leaves the range unbounded
+    end: str = ""
+    include: bool = False
+
+
+class _Reader:
+    __slots__ = ("data", "endian", "offset")
+
+    def __init__(self, data: bytes, endian: str) -> None:
+        self.data: bytes = data
+        self.endian: str = endian
+        self.offset: int = 0
+
+    def at_end(self) -> bool:
+        return self.offset >= len(self.data)
+
+    def unpack(self, fmt: str) -> Tuple[int, ...]:
+        values = struct.unpack_from(self.endian + fmt, self.data, self.offset)
+        self.offset += struct.calcsize(self.endian + fmt)
+        return values
+
+    def octet_string(self) -> bytes:
+        (length,) = self.unpack("I")
+        if self.offset + length > len(self.data):
+            raise ValueError(f"Octet string length {length} exceeds the payload")
+        value = self.data[self.offset : self.offset + length]
+        self.offset += length + -length % 4
+        return value
+
+    def oid(self) -> Tuple[str, bool]:
+        n_subid, prefix, include, _ = self.unpack("BBBB")
+        subids = list(self.unpack(f"{n_subid}I"))
+        if prefix:
+            subids = [*_INTERNET_PREFIX, prefix, *subids]
+        return "".join(f".{subid}" for subid in subids), bool(include)
+
+    def search_ranges(self) -> List[SearchRange]:
+        ranges: List[SearchRange] = []
+        while not self.at_end():
+            start, include = self.oid()
+            end, _ = self.oid()
+            ranges.append(SearchRange(start, end, include))
+        return ranges
+
+    def varbind(self) -> SnmpVarbind:
+        number, _ = self.unpack("HH")
+        oid, _ = self.oid()
+        value: Union[None, str, int, bytes, ipaddress.IPv4Address]
+        if number == Number.Integer:
+            (value,) = self.unpack("i")
+        elif number in (Number.Counter32, Number.Gauge32, Number.TimeTicks):
+            (value,) = self.unpack("I")
+        elif number == Number.Counter64:
+            (value,) = self.unpack("Q")
+        elif number in (Number.OctetString, Number.Opaque):
+            value = self.octet_string()
+        elif number == Number.IPAddress:
+            value = ipaddress.IPv4Address(self.octet_string())
+        elif number == Number.ObjectIdentifier:
+            value, _ = self.oid()
+        elif number in (Number.Null, Number.NoSuchObject, Number.NoSuchInstance, Number.EndOfMibView):
+            value = None
+        else:
+            raise ValueError(f"Unknown AgentX varbind type {number}")
+        return SnmpVarbind(oid, value, number)
+
+    def varbinds(self) -> List[SnmpVarbind]:
+        varbinds: List[SnmpVarbind] = []
+        while not self.at_end():
+            varbinds.append(self.varbind())
+        return varbinds
+
+
+class _Writer:
+    __slots__ = ("buffer", "endian")
+
+    def __init__(self, endian: str) -> None:
+        self.buffer: bytearray = bytearray()
+        self.endian: str = endian
+
+    def pack(self, fmt: str, *values: int) -> None:
+        self.buffer += struct.pack(self.endian + fmt, *values)
+
+    def octet_string(self, value: bytes) -> None:
+        self.pack("I", len(value))
+        self.buffer += value + bytes(-len(value) % 4)
+
+    def oid(self, oid: str, include: bool = False) -> None:
+        subids = [int(subid) for subid in oid.strip(".").split(".")] if oid.strip(".") else []
+        prefix = 0
+        if len(subids) >= 5 and tuple(subids[:4]) == _INTERNET_PREFIX and 0 < subids[4] < 256:
+            prefix = subids[4]
+            subids = subids[5:]
+        if len(subids) > 128:
+            raise ValueError(f"AgentX object identifiers have at most 128 sub-identifiers, got {len(subids)}")
+        self.pack("BBBB", len(subids), prefix, int(include), 0)
+        self.pack(f"{len(subids)}I", *subids)
+
+    def search_ranges(self, ranges: List[SearchRange]) -> None:
+        for search_range in ranges:
+            self.oid(search_range.start, search_range.include)
+            self.oid(search_range.end)
+
+    def varbind(self, varbind: SnmpVarbind) -> None:
+        value = varbind.value
+        number = varbind.number
+        if number is None:
+            if isinstance(value, int):
+                number = Number.Integer
+            elif isinstance(value, (str, bytes)):
+                number = Number.OctetString
+            elif isinstance(value, ipaddress.IPv4Address):
+                number = Number.IPAddress
+            else:
+                number = Number.Null
+        self.pack("HH", number, 0)
+        self.oid(varbind.oid)
+        if number == Number.Integer and isinstance(value, int):
+            self.pack("i", value)
+        elif number in (Number.Counter32, Number.Gauge32, Number.TimeTicks) and isinstance(value, int):
+            self.pack("I", value)
+        elif number == Number.Counter64 and isinstance(value, int):
+            self.pack("Q", value)
+        elif number == Number.ObjectIdentifier and isinstance(value, str):
+            self.oid(value)
+        elif number == Number.IPAddress and isinstance(value, ipaddress.IPv4Address):
+            self.octet_string(value.packed)
+        elif number in (Number.OctetString, Number.Opaque) and isinstance(value, (str, bytes)):
+            self.octet_string(value.encode() if isinstance(value, str) else value)
+        elif number not in (Number.Null, Number.NoSuchObject, Number.NoSuchInstance, Number.EndOfMibView):
+            raise ValueError(f"Cannot encode {type(value)} as AgentX varbind type {number}")
+
+    def varbinds(self, varbinds: List[SnmpVarbind]) -> None:
+        for varbind in varbinds:
+            self.varbind(varbind)
+
+
+class AgentXPDU:
+    __slots__ = ("session_id", "transaction_id", "packet_id", "flags", "context")
+
+    _PDUType: AgentXType
+    # Open, Close and Response PDUs never carry a context
+    _HasContext: bool = True
+
+    def __init__(self) -> None:
+        self.session_id: int = 0
+        self.transaction_id: int = 0
+        self.packet_id: int = 0
+        # InstanceRegistration, NewIndex and AnyIndex, the other flags are set by encode_pdu
+        self.flags: int = 0
+        self.context: Optional[bytes] = None
+
+    def _encode(self, writer: _Writer) -> None:
+        pass
+
+    @classmethod
+    def _decode(cls, reader: _Reader) -> "AgentXPDU":
+        return cls()
+
+
+class AgentXOpen(AgentXPDU):
+    __slots__ = ("timeout", "oid", "description")
+
+    _PDUType = AgentXType.Open
+    _HasContext = False
+
+    def __init__(self, timeout: int, oid: str, description: bytes) -> None:
+        super().__init__()
+        self.timeout: int = timeout
+        self.oid: str = oid
+        self.description: bytes = description
+
+    def _encode(self, writer: _Writer) -> None:
+        writer.pack("B3x", self.timeout)
+        writer.oid(self.oid)
+        writer.octet_string(self.description)
+
+    @classmethod
+    def _decode(cls, reader: _Reader) -> "AgentXOpen":
+        (timeout,) = reader.unpack("B3x")
+        oid, _ = reader.oid()
+        return cls(timeout, oid, reader.octet_string())
+
+
+class AgentXClose(AgentXPDU):
+    __slots__ = ("reason",)
+
+    _PDUType = AgentXType.Close
+    _HasContext = False
+
+    def __init__(self, reason: int = CloseReason.Shutdown) -> None:
+        super().__init__()
+        self.reason: int = reason
+
+    def _encode(self, writer: _Writer) -> None:
+        writer.pack("B3x", self.reason)
+
+    @classmethod
+    def _decode(cls, reader: _Reader) -> "AgentXClose":
+        (reason,) = reader.unpack("B3x")
+        return cls(reason)
+
+
+class AgentXRegister(AgentXPDU):
+    __slots__ = ("subtree", "timeout", "priority", "range_subid", "upper_bound")
+
+    _PDUType = AgentXType.Register
+
+    def __init__(
+        self, subtree: str, *, timeout: int = 0, priority: int = 127, range_subid: int = 0, upper_bound: int = 0
+    ) -> None:
+        super().__init__()
+        self.subtree: str = subtree
+        self.timeout: int = timeout
+        self.priority: int = priority
+        # with range_subid, the sub-identifier at that 1-based position ranges up to upper_bound
+        self.range_subid: int = range_subid
+        self.upper_bound: int = upper_bound
+
+    def _encode(self, writer: _Writer) -> None:
+        writer.pack("BBBx", self.timeout, self.priority, self.range_subid)
+        writer.oid(self.subtree)
+        if self.range_subid:
+            writer.pack("I", self.upper_bound)
+
+    @classmethod
+    def _decode(cls, reader: _Reader) -> "AgentXRegister":
+        timeout, priority, range_subid = reader.unpack("BBBx")
+        subtree, _ = reader.oid()
+        upper_bound = reader.unpack("I")[0] if range_subid else 0
+        return cls(subtree, timeout=timeout, priority=priority, range_subid=range_subid, upper_bound=upper_bound)
+
+
+class AgentXUnregister(AgentXRegister):
+    __slots__ = ()
+
+    # the timeout octet is reserved in Unregister PDUs
+    _PDUType = AgentXType.Unregister
+
+
+class AgentXGet(AgentXPDU):
+    __slots__ = ("ranges",)
+
+    _PDUType = AgentXType.Get
+
+    def __init__(self, ranges: List[SearchRange]) -> None:
+        super().__init__()
+        self.ranges: List[SearchRange] = ranges
+
+    def _encode(self, writer: _Writer) -> None:
+        writer.search_ranges(self.ranges)
+
+    @classmethod
+    def _decode(cls, reader: _Reader) -> "AgentXGet":
+        return cls(reader.search_ranges())
+
+
+class AgentXGetNext(AgentXGet):
+    __slots__ = ()
+
+    _PDUType = AgentXType.GetNext
+
+
+class AgentXGetBulk(AgentXPDU):
+    __slots__ = ("non_repeaters", "max_repetitions", "ranges")
+
+    _PDUType = AgentXType.GetBulk
+
+    def __init__(self, non_repeaters: int, max_repetitions: int, ranges: List[SearchRange]) -> None:
+        super().__init__()
+        self.non_repeaters: int = non_repeaters
+        self.max_repetitions: int = max_repetitions
+        self.ranges: List[SearchRange] = ranges
+
+    def _encode(self, writer: _Writer) -> None:
+        writer.pack("HH", self.non_repeaters, self.max_repetitions)
+        writer.search_ranges(self.ranges)
+
+    @classmethod
+    def _decode(cls, reader: _Reader) -> "AgentXGetBulk":
+        non_repeaters, max_repetitions = reader.unpack("HH")
+        return cls(non_repeaters, max_repetitions, reader.search_ranges())
+
+
+class AgentXTestSet(AgentXPDU):
+    __slots__ = ("varbinds",)
+
+    _PDUType = AgentXType.TestSet
+
+    def __init__(self, varbinds: List[SnmpVarbind]) -> None:
+        super().__init__()
+        self.varbinds: List[SnmpVarbind] = varbinds
+
+    def _encode(self, writer: _Writer) -> None:
+        writer.varbinds(self.varbinds)
+
+    @classmethod
+    def _decode(cls, reader: _Reader) -> "AgentXTestSet":
+        return cls(reader.varbinds())
+
+
+class AgentXNotify(AgentXTestSet):
+    __slots__ = ()
+
+    # sysUpTime.0 (optional) and snmpTrapOID.0 come first, like in SNMPv2 traps
+    _PDUType = AgentXType.Notify
+
+
+class AgentXCommitSet(AgentXPDU):
+    __slots__ = ()
+
+    _PDUType = AgentXType.CommitSet
+    _HasContext = False
+
+
+class AgentXUndoSet(AgentXPDU):
+    __slots__ = ()
+
+    _PDUType = AgentXType.UndoSet
+    _HasContext = False
+
+
+class AgentXCleanupSet(AgentXPDU):
+    __slots__ = ()
+
+    _PDUType = AgentXType.CleanupSet
+    _HasContext = False
+
+
+class AgentXPing(AgentXPDU):
+    __slots__ = ()
+
+    _PDUType = AgentXType.Ping
+
+
+class AgentXResponse(AgentXPDU):
+    __slots__ = ("sys_uptime", "error", "index", "varbinds")
+
+    _PDUType = AgentXType.Response
+    _HasContext = False
+
+    def __init__(
+        self,
+        sys_uptime: int = 0,
+        error: int = AgentXError.NoAgentXError,
+        index: int = 0,
+        varbinds: Optional[List[SnmpVarbind]] = None,
+    ) -> None:
+        super().__init__()
+        self.sys_uptime: int = sys_uptime
+        self.error: int = error
+        self.index: int = index
+        self.varbinds: List[SnmpVarbind] = varbinds if varbinds is not None else []
+
+    def _encode(self, writer: _Writer) -> None:
+        writer.pack("IHH", self.sys_uptime, self.error, self.index)
+        writer.varbinds(self.varbinds)
+
+    @classmethod
+    def _decode(cls, reader: _Reader) -> "AgentXResponse":
+        sys_uptime, error, index = reader.unpack("IHH")
+        return cls(sys_uptime, error, index, reader.varbinds())
+
+
+_PDU_CLASSES: Dict[int, Type[AgentXPDU]] = {
+    pdu_cls._PDUType: pdu_cls
+    for pdu_cls in (
+        AgentXOpen,
+        AgentXClose,
+        AgentXRegister,
+        AgentXUnregister,
+        AgentXGet,
+        AgentXGetNext,
+        AgentXGetBulk,
+        AgentXTestSet,
+        AgentXCommitSet,
+        AgentXUndoSet,
+        AgentXCleanupSet,
+        AgentXNotify,
+        AgentXPing,
+        AgentXResponse,
+    )
+}
+
+
+def encode_pdu(pdu: AgentXPDU, *, network_byte_order: bool = True) -> bytes:
+    flags = pdu.flags & ~(AgentXFlags.NonDefaultContext | AgentXFlags.NetworkByteOrder)
+    if network_byte_order:
+        flags |= AgentXFlags.NetworkByteOrder
+    writer = _Writer("!" if network_byte_order else "<")
+    if pdu.context is not None and pdu._HasContext:
+        flags |= AgentXFlags.NonDefaultContext
+        writer.octet_string(pdu.context)
+    try:
+        pdu._encode(writer)
+    except struct.error as exc:
+        raise ValueError(f"Cannot encode AgentX {pdu._PDUType.name} PDU: {exc}")
+
+    header = _Writer(writer.endian)
+    header.pack("BBBx", _VERSION, pdu._PDUType, flags)
+    header.pack("IIII", pdu.session_id, pdu.transaction_id, pdu.packet_id, len(writer.buffer))
+    return bytes(header.buffer + writer.buffer)
+
+
+def decode_header(data: bytes) -> AgentXHeader:
+    # the payload length tells how many octets to read after the header on a stream
+    if len(data) < HEADER_SIZE:
+        raise ValueError(f"AgentX header needs {HEADER_SIZE} octets, got {len(data)}")
+    version, pdu_type, flags = struct.unpack_from("BBBx", data)
+    if version != _VERSION:
+        raise ValueError(f"Unsupported AgentX version {version}")
+    endian = "!" if flags & AgentXFlags.NetworkByteOrder else "<"
+    return AgentXHeader(pdu_type, flags, *struct.unpack_from(f"{endian}IIII", data, 4))
+
+
+def decode_pdu(data: bytes) -> AgentXPDU:
+    header = decode_header(data)
+    if len(data) != HEADER_SIZE + header.payload_length:
+        raise ValueError(f"AgentX payload length {header.payload_length} does not match {len(data) - HEADER_SIZE}")
+    pdu_cls = _PDU_CLASSES.get(header.pdu_type)
+    if pdu_cls is None:
+        raise ValueError(f"Unsupported AgentX PDU type {header.pdu_type}")
+
+    reader = _Reader(data[HEADER_SIZE:], header.endian)
+    try:
+        context = reader.octet_string() if header.flags & AgentXFlags.NonDefaultContext else None
+        pdu = pdu_cls._decode(reader)
+    except struct.error as exc:
+        raise ValueError(f"Truncated AgentX {pdu_cls._PDUType.name} PDU: {exc}")
+    if not reader.at_end():
+        raise ValueError(f"Trailing data after AgentX {pdu_cls._PDUType.name} PDU")
+
+    pdu.session_id = header.session_id
+    pdu.transaction_id = header.transaction_id
+    pdu.packet_id = header.packet_id
+    pdu.flags = header.flags & ~(AgentXFlags.NonDefaultContext | AgentXFlags.NetworkByteOrder)
+    pdu.context = context
+    return pdu
+
+
+async def read_pdu(reader: asyncio.StreamReader) -> AgentXPDU:
+    header = await reader.readexactly(HEADER_SIZE)
+    payload = await reader.readexactly(decode_header(header).payload_length)
+    return decode_pdu(header + payload)
@@ -4,11 +4,41 @@
 #
 # Python-ASN1 is copyright (c) 2007-2016 by the Python-ASN1 authors.
 
+__all__ = (
+    "Number",
+    "Type",
+    "Class",
+    "Tag",
+    "Header",
+    "Malformed",
+    "TaggedValue",
+    "OctetView",
+    "Field",
+    "Error",
+    "EncodeError",
+    "DecodeError",
+    "PrematureEndError",
+    "Asn1SyntaxError",
+    "LimitError",
+    "UnexpectedTagError",
+    "MessageTooBigError",
+    "Encoder",
+    "Decoder",
+    "StreamDecoder",
+    "message_boundaries",
+    "split_messages",
+    "encode",
+    "decode",
+    "dump",
+    "Mismatch",
+    "verify_roundtrip",
+)
+
 import enum
 import ipaddress
 import re
 from contextlib import contextmanager
-from typing import Any, Iterator, List, NamedTuple, Optional, Tuple, Union, cast
+from typing import Any, Callable, Dict, Iterable, Iterator, List, NamedTuple, Optional, Tuple, Union, cast
 
 
 class Number(enum.IntEnum):
@@ -24,9 +54,13 @@ class Number(enum.IntEnum):
     UTF8String = 0x0C
     Sequence = 0x10
     Set = 0x11
+    NumericString = 0x12
     PrintableString = 0x13
     IA5String = 0x16
     UTCTime = 0x17
+    VisibleString = 0x1A
+    BMPString = 0x1E
+    # former name of BMPString, kept as an alias
     UnicodeString = 0x1E
 
     IPAddress = 0x40
@@ -61,21 +95,282 @@ TType = Union[Type, int]
 TClass = Union[Class, int]
 
 
+_NUMBERS = frozenset(Number.__members__.values())
+
+
+def _is_short_tag(nr: TNumber, cls: TClass) -> bool:
+    """Return True if ``nr`` fits the first identifier octet."""
+    # ``Number`` members of the application and context classes carry the class bits already
+    return nr < 0x1F or isinstance(nr, Number) or (cls == Class.Universal and nr in _NUMBERS)
+
+_UNSIGNED_BITS = {
+    Number.Counter32: 32,
+    Number.Gauge32: 32,
+    Number.TimeTicks: 32,
+    Number.Uinteger32: 32,
+    Number.Counter64: 64,
+}
+
+
 class Tag(NamedTuple):
     nr: TNumber
-    typ: TType
+    typ: TType = Type.Primitive
+    cls: TClass = Class.Universal
+
+    @classmethod
+    def universal(cls, nr: TNumber, constructed: bool = False) -> "Tag":
+        return cls(nr, Type.Constructed if constructed else Type.Primitive, Class.Universal)
+
+    @classmethod
+    def application(cls, nr: TNumber, constructed: bool = False) -> "Tag":
+        return cls(nr, Type.Constructed if constructed else Type.Primitive, Class.Application)
+
+    @classmethod
+    def context(cls, nr: TNumber, constructed: bool = False) -> "Tag":
+        return cls(nr, Type.Constructed if constructed else Type.Primitive, Class.Context)
+
+    @classmethod
+    def private(cls, nr: TNumber, constructed: bool = False) -> "Tag":
+        return cls(nr, Type.Constructed if constructed else Type.Primitive, Class.Private)
+
+    @property
+    def number(self) -> TNumber:
+        """Alias of ``nr``, e.g. for ``case Tag(number=Number.Integer):`` patterns."""
+        return self.nr
+
+    @property
+    def is_constructed(self) -> bool:
+        return self.typ == Type.Constructed
+
+    @property
+    def class_name(self) -> str:
+        return Class(self.cls).name
+
+    @property
+    def type_name(self) -> str:
+        return Type(self.typ).name
+
+    @property
+    def tag_byte(self) -> int:
+        """The first identifier octet, high tag numbers are continued in the following octets."""
+        if _is_short_tag(self.nr, self.cls):
+            return self.nr | self.typ | self.cls
+        return 0x1F | self.typ | self.cls
+
+    def __repr__(self) -> str:
+        nr = hex(self.nr)
+        if self.nr < 0x1F and self.cls in (Class.Universal, Class.Application):
+            if self.nr | self.cls in Number.__members__.values():
+                nr = Number(self.nr | self.cls).name
+        return f"Tag(nr={nr}, typ={self.type_name}, cls={self.class_name})"
+
+
+class Header(NamedTuple):
+    tag: Tag
+    length: int
+    size: int
+
+
+class Malformed(NamedTuple):
+    data: bytes
+    error: "DecodeError"
+
+
+class TaggedValue(NamedTuple):
+    """Content octets of an application, context or private class tag that
+    has no ``Number`` member, written back verbatim by `Encoder.write()`."""
+
     cls: TClass
+    number: int
+    data: bytes
+    constructed: bool = False
+
+
+class OctetView:
+    """Read-only view of the content octets of a large OCTET STRING or
+    Opaque value, returned by a `Decoder` with ``view_threshold`` instead
+    of a copy of the octets. It references the decoder's input, which
+    therefore stays alive as long as the view does."""
+
+    __slots__ = ("_data", "_offset")
+
+    def __init__(self, data: Union[bytes, memoryview]) -> None:
+        self._data: memoryview = memoryview(data)
+        self._offset: int = 0
+
+    def __len__(self) -> int:
+        return len(self._data)
+
+    def __repr__(self) -> str:
+        return f"OctetView(length={len(self._data)}, offset={self._offset})"
+
+    def read(self, size: int = -1) -> bytes:
+        """This method copies the next ``size`` octets, or all remaining
+        ones if ``size`` is negative, like `io.RawIOBase.read()`.
+
+        Args:
+            size (int): Maximum number of octets to return.
+
+        Returns:
+            bytes: The octets, empty at the end of the value.
+        """
+        end = len(self._data) if size < 0 else min(self._offset + size, len(self._data))
+        chunk = self._data[self._offset : end].tobytes()
+        self._offset = end
+        return chunk
+
+    def to_bytes(self) -> bytes:
+        """This method copies the whole value, regardless of the position."""
+        return self._data.tobytes()
+
+
+class Field(NamedTuple):
+    name: str
+    # tag or number (including the class bits) the element must have, None accepts any tag
+    expect: Union[Tag, TNumber, None] = None
+    optional: bool = False
+    default: Any = None
+    # fields of a nested constructed type, decoded by `Decoder.read_struct()`
+    schema: Optional[Tuple["Field", ...]] = None
 
 
 class Error(Exception):
+    def __init__(
+        self,
+        message: str = "",
+        *,
+        offset: Optional[int] = None,
+        tag: Optional[Tag] = None,
+        depth: Optional[int] = None,
+    ) -> None:
+        super().__init__(message)
+        self.offset: Optional[int] = offset
+        self.tag: Optional[Tag] = tag
+        self.depth: Optional[int] = depth
+
+    def __reduce__(self) -> Tuple[Any, ...]:
+        # the context is passed by keyword, so it is restored as state, e.g. from a decode_many_parallel() worker
+        return type(self), self.args, self.__dict__
+
+    def __str__(self) -> str:
+        message = super().__str__()
+        context = []
+        if self.tag is not None:
+            context.append(f"tag: {Class(self.tag.cls).name} {self.tag.nr:#04x}")
+        if self.offset is not None:
+            context.append(f"offset: {self.offset}")
+        if self.depth is not None:
+            context.append(f"depth: {self.depth}")
+        if context:
+            message = f"{message} ({', '.join(context)})"
+        return message
+
+
+class EncodeError(Error):
     pass
 
 
+class DecodeError(Error):
+    pass
+
+
+class PrematureEndError(DecodeError):
+    pass
+
+
+class Asn1SyntaxError(DecodeError):
+    pass
+
+
+class LimitError(DecodeError):
+    pass
+
+
+class UnexpectedTagError(DecodeError):
+    pass
+
+
+class MessageTooBigError(EncodeError):
+    pass
+
+
+_EncoderState = Tuple[bytes, List[Tuple[int, int, int, int]], List[Tuple[int, bytes]], int, Optional[int]]
+
+
 class Encoder:
-    __slots__ = "m_stack"
+    __slots__ = ("m_buffer", "m_size", "m_stack", "m_patches", "m_extra", "m_max_size")
 
-    def __init__(self) -> None:
-        self.m_stack: List[List[bytes]] = [[]]
+    def __init__(self, *, max_size: Optional[int] = None, capacity: int = 0) -> None:
+        """
+        Args:
+            max_size (int): Optional limit of the output size in octets,
+                `MessageTooBigError` is raised by `Encoder.output()` and
+                `Encoder.output_into()` for larger output, e.g. to not send
+                datagrams that would be truncated.
+
+            capacity (int): Number of octets to allocate the buffer with,
+                e.g. the expected datagram size, so encoding up to that size
+                does not grow it. See `Encoder.reserve()`.
+        """
+        # the whole output, constructed types are encoded in place with one octet reserved for their length
+        self.m_buffer: bytearray = bytearray(capacity)
+        # number of octets of m_buffer in use, the rest is preallocated
+        self.m_size: int = 0
+        # (tag offset, value offset, m_extra, number of patches) per unfinished constructed type
+        self.m_stack: List[Tuple[int, int, int, int]] = []
+        # (offset of the reserved octet, length octets) of lengths in the long form, which are
+        # spliced in once by the output methods instead of shifting the buffer on every exit
+        self.m_patches: List[Tuple[int, bytes]] = []
+        # octets the patches add to the buffer
+        self.m_extra: int = 0
+        self.m_max_size: Optional[int] = max_size
+
+    def __getstate__(self) -> _EncoderState:
+        buffer = bytes(self.m_buffer[: self.m_size])
+        return buffer, list(self.m_stack), list(self.m_patches), self.m_extra, self.m_max_size
+
+    def __setstate__(self, state: _EncoderState) -> None:
+        buffer, stack, patches, self.m_extra, self.m_max_size = state
+        self.m_buffer = bytearray(buffer)
+        self.m_size = len(buffer)
+        self.m_stack = list(stack)
+        self.m_patches = list(patches)
+
+    def __copy__(self) -> "Encoder":
+        encoder = type(self).__new__(type(self))
+        encoder.__setstate__(self.__getstate__())
+        return encoder
+
+    def __deepcopy__(self, memo: Dict[int, Any]) -> "Encoder":
+        return self.__copy__()
+
+    def reset(self) -> None:
+        """This method discards everything that has been encoded so far,
+        including unfinished constructed types, so the encoder can be
+        reused for the next message. The allocated buffer is kept.
+
+        Returns:
+            None
+        """
+        self.m_size = 0
+        self.m_stack.clear()
+        self.m_patches.clear()
+        self.m_extra = 0
+
+    def reserve(self, size: int) -> None:
+        """This method grows the buffer at once, so that the next ``size``
+        octets of output are written without reallocating it, e.g. before
+        writing a large value.
+
+        Args:
+            size (int): Number of octets to make room for.
+
+        Returns:
+            None
+        """
+        missing = self.m_size + size - len(self.m_buffer)
+        if missing > 0:
+            self.m_buffer.extend(bytes(missing))
 
     @contextmanager
     def enter(self, nr: TNumber, cls: Optional[TClass] = None) -> Iterator[None]:
@@ -88,6 +383,10 @@ class Encoder:
                 of the constructed type. The default class to use is the
                 universal class. Use ``Class`` enumeration.
 
+        Note:
+            The length is emitted when the ``with`` block exits. If it exits
+            with an exception, the unfinished constructed type is discarded.
+
         Returns:
             None
 
@@ -96,17 +395,30 @@ class Encoder:
         """
         if cls is None:
             cls = Class.Universal
+        start = self.m_size
         self._emit_tag(nr, Type.Constructed, cls)
-        self.m_stack.append([])
+        # one octet is reserved for the length, longer length forms become patches on exit
+        self._emit(b"\x00")
+        self.m_stack.append((start, self.m_size, self.m_extra, len(self.m_patches)))
 
-        yield
-
-        if len(self.m_stack) == 1:
-            raise Error("Tag stack is empty.")
-        value = b"".join(self.m_stack[-1])
-        del self.m_stack[-1]
-        self._emit_length(len(value))
-        self._emit(value)
+        try:
+            yield
+        except BaseException:
+            # drop the unfinished constructed type together with its tag and nested patches
+            if self.m_stack:
+                self.m_size, _, self.m_extra, patches = self.m_stack.pop()
+                del self.m_patches[patches:]
+            raise
+
+        if not self.m_stack:
+            raise EncodeError("Tag stack is empty.")
+        _, offset, extra, _ = self.m_stack.pop()
+        length = self._encode_length(self.m_size - offset + self.m_extra - extra)
+        if len(length) == 1:
+            self.m_buffer[offset - 1] = length[0]
+        else:
+            self.m_patches.append((offset - 1, length))
+            self.m_extra += len(length) - 1
 
     def write(
         self,
@@ -114,6 +426,8 @@ class Encoder:
         nr: Optional[TNumber] = None,
         typ: Optional[TType] = None,
         cls: Optional[TClass] = None,
+        *,
+        explicit: Optional[int] = None,
     ) -> None:
         """This method encodes one ASN.1 tag and writes it to the output buffer.
 
@@ -126,7 +440,8 @@ class Encoder:
         Args:
             value (any): The value of the ASN.1 tag to write. Python-ASN1 will
                 try to autodetect the correct ASN.1 type from the type of
-                ``value``.
+                ``value``. A list or tuple is written as a SEQUENCE of its
+                elements.
 
             nr (int): If the desired ASN.1 type cannot be autodetected or is
                 autodetected wrongly, the ``nr`` parameter can be provided to
@@ -144,12 +459,29 @@ class Encoder:
                 ``value``. The default class is the universal class.
                 Use ``Class`` enumeration.
 
+            explicit (int): If given, the encoded value is wrapped in an outer
+                constructed context-specific tag with this number, i.e. it is
+                explicitly tagged as ``[explicit] EXPLICIT``.
+
         Returns:
             None
 
         Raises:
             `Error`
         """
+        if explicit is not None:
+            with self.enter(explicit, Class.Context):
+                self.write(value, nr, typ, cls)
+            return
+        if isinstance(value, TaggedValue) and typ is None and cls is None:
+            # the value carries its own tag, ``nr`` is ignored
+            self._emit_tag(value.number, Type.Constructed if value.constructed else Type.Primitive, value.cls)
+            self._emit_length(len(value.data))
+            self._emit(value.data)
+            return
+        if nr is None and isinstance(value, (list, tuple)):
+            self.write_sequence(value)
+            return
         if nr is None:
             if isinstance(value, int):
                 nr = Number.Integer
@@ -160,16 +492,80 @@ class Encoder:
             elif isinstance(value, ipaddress.IPv4Address):
                 nr = Number.IPAddress
             else:
-                raise Error(f"Cannot determine Number for value type {type(value)}")
+                raise EncodeError(f"Cannot determine Number for value type {type(value)}")
         if typ is None:
             typ = Type.Primitive
         if cls is None:
             cls = Class.Universal
-        value = self._encode_value(nr, value)
+        try:
+            value = self._encode_value(nr, value)
+        except Error as exc:
+            exc.tag = Tag(nr, typ, cls)
+            exc.depth = len(self.m_stack)
+            raise
         self._emit_tag(nr, typ, cls)
         self._emit_length(len(value))
         self._emit(value)
 
+    def write_sequence(self, values: Iterable[Any]) -> None:
+        """This method encodes a SEQUENCE of the given values. Every element
+        is encoded as by `Encoder.write()`, so nested lists and tuples become
+        nested sequences.
+
+        Args:
+            values (iterable): The elements of the sequence.
+
+        Returns:
+            None
+
+        Raises:
+            `Error`
+        """
+        with self.enter(Number.Sequence):
+            for value in values:
+                self.write(value)
+
+    def write_set_of(self, values: Iterable[Any], nr: Optional[TNumber] = None) -> None:
+        """This method encodes a SET OF with the elements sorted by their
+        encodings, as required by DER.
+
+        Args:
+            values (iterable): The elements of the set. Each element is
+                encoded as by `Encoder.write()`.
+
+            nr (int): The ASN.1 type of the elements, autodetected from
+                each value when omitted. Use ``Number`` enumeration.
+
+        Returns:
+            None
+
+        Raises:
+            `Error`
+        """
+        encoder = Encoder()
+        encoded = []
+        for value in values:
+            encoder.write(value, nr)
+            encoded.append(encoder.output())
+            encoder.reset()
+        encoded.sort()
+        with self.enter(Number.Set):
+            for element in encoded:
+                self._emit(element)
+
+    def write_raw(self, data: bytes) -> None:
+        """This method appends already encoded ASN.1 data verbatim to the
+        current constructed type, e.g. to splice a received PDU into a new
+        message without decoding it.
+
+        Args:
+            data (bytes): Complete encoded tag, length and value octets.
+
+        Returns:
+            None
+        """
+        self._emit(bytes(data))
+
     def output(self) -> bytes:
         """This method returns the encoded ASN.1 data as plain Python ``bytes``.
         This method can be called multiple times, also during encoding.
@@ -187,33 +583,87 @@ class Encoder:
         Raises:
             `Error`
         """
-        if len(self.m_stack) != 1:
-            raise Error("Stack is not empty.")
-        output = b"".join(self.m_stack[0])
-        return output
+        if self.m_stack:
+            raise EncodeError("Stack is not empty.")
+        self._check_size(self.m_size + self.m_extra)
+        return b"".join(self._chunks())
+
+    def output_into(self, buffer: Union[bytearray, memoryview]) -> int:
+        """This method writes the encoded ASN.1 data into a pre-allocated
+        writable buffer, starting at its beginning.
+
+        Args:
+            buffer (bytearray or memoryview): The destination buffer. It
+                must be large enough to hold the whole encoded output.
+
+        Returns:
+            int: The number of bytes written.
+
+        Raises:
+            `Error`
+        """
+        if self.m_stack:
+            raise EncodeError("Stack is not empty.")
+        view = memoryview(buffer).cast("B")
+        if view.readonly:
+            raise EncodeError("Output buffer is read-only.")
+        size = self.m_size + self.m_extra
+        self._check_size(size)
+        if size > len(view):
+            raise EncodeError(f"Output buffer is too small, {size} bytes needed, {len(view)} available.")
+        offset = 0
+        for chunk in self._chunks():
+            view[offset : offset + len(chunk)] = chunk
+            offset += len(chunk)
+        return size
+
+    def _chunks(self) -> Iterator[Union[bytes, memoryview]]:
+        """Yield the output in order, with the long length forms spliced in."""
+        buffer = memoryview(self.m_buffer)[: self.m_size]
+        position = 0
+        # patches are added when leaving a constructed type, inner ones first
+        for offset, length in sorted(self.m_patches):
+            yield buffer[position:offset]
+            yield length
+            position = offset + 1
+        yield buffer[position:]
+
+    def _check_size(self, size: int) -> None:
+        """Raise an error if the output exceeds the size limit."""
+        if self.m_max_size is not None and size > self.m_max_size:
+            raise MessageTooBigError(f"Encoded size {size} exceeds the limit of {self.m_max_size} octets.")
 
     def _emit_tag(self, nr: TNumber, typ: TType, cls: TClass) -> None:
         """Emit a tag."""
-        self._emit_tag_short(nr, typ, cls)
+        if _is_short_tag(nr, cls):
+            self._emit_tag_short(nr, typ, cls)
+        else:
+            self._emit_tag_long(nr, typ, cls)
 
     def _emit_tag_short(self, nr: TNumber, typ: TType, cls: TClass) -> None:
         """Emit a short tag."""
         self._emit(bytes([nr | typ | cls]))
 
+    def _emit_tag_long(self, nr: TNumber, typ: TType, cls: TClass) -> None:
+        """Emit a long tag (tag number >= 31)."""
+        values = [nr & 0x7F]
+        nr >>= 7
+        while nr:
+            values.append(0x80 | (nr & 0x7F))
+            nr >>= 7
+        values.append(0x1F | typ | cls)
+        values.reverse()
+        self._emit(bytes(values))
+
     def _emit_length(self, length: int) -> None:
         """Emit length octets."""
-        if length < 128:
-            self._emit_length_short(length)
-        else:
-            self._emit_length_long(length)
-
-    def _emit_length_short(self, length: int) -> None:
-        """Emit the short length form (< 128 octets)."""
-        assert length < 128
-        self._emit(bytes([length]))
+        self._emit(self._encode_length(length))
 
-    def _emit_length_long(self, length: int) -> None:
-        """Emit the long length form (>= 128 octets)."""
+    @staticmethod
+    def _encode_length(length: int) -> bytes:
+        """Encode the short (< 128 octets) or long length form."""
+        if length < 128:
+            return bytes([length])
         values = []
         while length:
             values.append(length & 0xFF)
@@ -221,22 +671,37 @@ class Encoder:
         values.reverse()
         # really for correctness as this should not happen anytime soon
         assert len(values) < 127
-        head = bytes([0x80 | len(values)])
-        self._emit(head)
-        for val in values:
-            self._emit(bytes([val]))
+        return bytes([0x80 | len(values)] + values)
 
     def _emit(self, s: bytes) -> None:
         """Emit raw bytes."""
         assert isinstance(s, bytes)
-        self.m_stack[-1].append(s)
+        end = self.m_size + len(s)
+        # overwrites preallocated octets, the buffer only grows past its end
+        self.m_buffer[self.m_size : end] = s
+        self.m_size = end
 
     def _encode_value(self, nr: TNumber, value: Any) -> bytes:
         """Encode a value."""
         if nr in (Number.Integer, Number.Enumerated):
             return self._encode_integer(value)
-        elif nr in (Number.OctetString, Number.PrintableString):
+        elif nr in _UNSIGNED_BITS:
+            if not 0 <= value < 2 ** _UNSIGNED_BITS[nr]:
+                raise EncodeError(f"{Number(nr).name} value should fit {_UNSIGNED_BITS[nr]} unsigned bits, got {value}")
+            # a leading 0x00 keeps values with the high bit set positive, as BER requires
+            return self._encode_integer(value)
+        elif nr in (
+            Number.OctetString,
+            Number.PrintableString,
+            Number.UTF8String,
+            Number.IA5String,
+            Number.NumericString,
+            Number.VisibleString,
+            Number.UTCTime,
+        ):
             return self._encode_octet_string(value)
+        elif nr == Number.BMPString:
+            return self._encode_bmp_string(value)
         elif nr == Number.Boolean:
             return self._encode_boolean(value)
         elif nr == Number.Null:
@@ -245,7 +710,12 @@ class Encoder:
             return self._encode_object_identifier(value)
         elif nr == Number.IPAddress:
             return self._encode_ipaddress(value)
-        raise Error(f"Unhandled Number {nr} value {value}")
+        elif nr in (Number.EndOfMibView, Number.NoSuchObject, Number.NoSuchInstance) and value is None:
+            return b""
+        elif isinstance(value, bytes):
+            # content octets of types without a Python representation, as decoded
+            return value
+        raise EncodeError(f"Unhandled Number {nr} value {value}")
 
     @staticmethod
     def _encode_boolean(value: bool) -> bytes:
@@ -255,32 +725,9 @@ class Encoder:
     @staticmethod
     def _encode_integer(value: int) -> bytes:
         """Encode an integer."""
-        if value < 0:
-            value = -value
-            negative = True
-            limit = 0x80
-        else:
-            negative = False
-            limit = 0x7F
-        values = []
-        while value > limit:
-            values.append(value & 0xFF)
-            value >>= 8
-        values.append(value & 0xFF)
-        if negative:
-            # create two's complement
-            for i in range(len(values)):  # Invert bits
-                values[i] = 0xFF - values[i]
-            for i in range(len(values)):  # Add 1
-                values[i] += 1
-                if values[i] <= 0xFF:
-                    break
-                assert i != len(values) - 1
-                values[i] = 0x00
-        if negative and values[len(values) - 1] == 0x7F:  # Two's complement corner case
-            values.append(0xFF)
-        values.reverse()
-        return bytes(values)
+        # minimal two's complement, Python ints are not limited to any width
+        length = ((value if value >= 0 else ~value).bit_length() + 8) // 8
+        return int(value).to_bytes(length, byteorder="big", signed=True)
 
     @staticmethod
     def _encode_octet_string(value: Union[str, bytes]) -> bytes:
@@ -301,10 +748,10 @@ class Encoder:
     def _encode_object_identifier(self, oid: str) -> bytes:
         """Encode an object identifier."""
         if not self._re_oid.match(oid):
-            raise Error("Illegal object identifier")
+            raise EncodeError("Illegal object identifier")
         cmps = list(map(int, oid.split(".")))
         if cmps[0] > 39 or cmps[1] > 39:
-            raise Error("Illegal object identifier")
+            raise EncodeError("Illegal object identifier")
         cmps = [40 * cmps[0] + cmps[1]] + cmps[2:]
         cmps.reverse()
         result = []
@@ -316,6 +763,13 @@ class Encoder:
         result.reverse()
         return bytes(result)
 
+    @staticmethod
+    def _encode_bmp_string(value: str) -> bytes:
+        """Encode a BMPString, characters outside the BMP are not representable."""
+        if any(ord(char) > 0xFFFF for char in value):
+            raise EncodeError("BMPString can only hold characters of the Basic Multilingual Plane")
+        return value.encode("utf-16-be")
+
     @staticmethod
     def _encode_ipaddress(value: ipaddress.IPv4Address) -> bytes:
         """Encode an ip address."""
@@ -323,11 +777,212 @@ class Encoder:
 
 
 class Decoder:
-    __slots__ = ("m_stack", "m_tag")
-
-    def __init__(self, data: bytes) -> None:
-        self.m_stack: List[List] = [[0, data]]
+    __slots__ = (
+        "m_stack",
+        "m_tag",
+        "m_tag_offset",
+        "m_max_depth",
+        "m_max_length",
+        "m_strict",
+        "m_lenient",
+        "m_ignore_trailing",
+        "m_trailing",
+        "m_hex_strings",
+        "m_strip_padding",
+        "m_tagged_values",
+        "m_lazy",
+        "m_view_threshold",
+        "m_ipv6_addresses",
+        "m_errors",
+    )
+
+    def __init__(
+        self,
+        data: bytes,
+        *,
+        max_depth: Optional[int] = None,
+        max_length: Optional[int] = None,
+        strict: bool = False,
+        lenient: bool = False,
+        ignore_trailing: bool = False,
+        hex_strings: bool = False,
+        strip_padding: bool = False,
+        tagged_values: bool = False,
+        lazy: bool = False,
+        ipv6_addresses: bool = False,
+        partial: bool = False,
+        view_threshold: Optional[int] = None,
+        start: int = 0,
+        length: Optional[int] = None,
+    ) -> None:
+        """
+        Args:
+            data (bytes): The ASN.1 encoded input. A ``bytearray`` or
+                ``memoryview``, like a preallocated receive buffer, is
+                decoded in place, see ``start`` and ``length``.
+
+            strict (bool): Reject encodings that BER allows but DER forbids,
+                like lengths or integers that are not encoded in the minimal
+                number of octets or booleans true other than 0xFF.
+
+            lenient (bool): Accept malformed values emitted by some legacy
+                agents: empty integers decode to 0, null with content to None
+                and object identifiers with less than two arcs or with a
+                first component above 1599 are decoded as far as possible.
+
+            ignore_trailing (bool): Ignore octets after the first complete
+                top-level tag, like padding appended by some devices. See
+                `Decoder.trailing_bytes`.
+
+            hex_strings (bool): Decode octet strings to ``str``, printable
+                ones as text and all others as ``0x`` prefixed hex like
+                net-snmp does, instead of ``bytes``.
+
+            strip_padding (bool): Remove trailing NUL and space octets that
+                some agents pad fixed width text columns with. Octet strings
+                are only stripped if the rest is non-empty printable text, so
+                binary values like MAC addresses are kept unchanged.
+
+            tagged_values (bool): Return values of application, context and
+                private class tags without a ``Number`` member as `TaggedValue`,
+                keeping their class and number, instead of plain ``bytes``.
+
+            lazy (bool): Make `Decoder.read()` return the undecoded content
+                octets as value, unless ``nr`` is given. They can be converted
+                later with `Decoder.decode_value()`.
+
+            ipv6_addresses (bool): Decode 16 octets IpAddress values, sent by
+                some agents, as ``ipaddress.IPv6Address``. Otherwise only 4
+                octets values are accepted.
+
+            partial (bool): Do not raise on malformed elements. `Decoder.read()`
+                and `Decoder.read_all()` record the error in `Decoder.errors`,
+                return a `Malformed` placeholder with the element's octets as
+                value and continue at the next element. If the element's
+                length can't be decoded either, the rest of the current
+                constructed type is skipped.
+
+            view_threshold (int): Return OCTET STRING and Opaque values of at
+                least this many octets as `OctetView` referencing the input,
+                instead of copying them to ``bytes``, e.g. firmware images to
+                be streamed to disk. ``hex_strings`` and ``strip_padding`` do
+                not apply to them.
+
+            max_depth (int): Optional limit of constructed types nesting,
+                `LimitError` is raised when entering deeper.
+
+            max_length (int): Optional limit of a single tag's value length
+                in octets, `LimitError` is raised for longer values.
+
+            start (int): Offset of the input within ``data``.
+
+            length (int): Number of octets of the input, by default up to
+                the end of ``data``. With ``start`` or ``length``, ``data`` is
+                decoded through a ``memoryview`` window without copying it.
+                Error offsets are relative to ``start``.
+        """
+        # [offset, data, offset of data within the whole input] per constructed type
+        self.m_stack: List[List] = [[0, self._window(data, start, length), 0]]
         self.m_tag: Optional[Tag] = None
+        self.m_tag_offset: int = 0
+        self.m_max_depth: Optional[int] = max_depth
+        self.m_max_length: Optional[int] = max_length
+        self.m_strict: bool = strict
+        self.m_lenient: bool = lenient
+        self.m_ignore_trailing: bool = ignore_trailing
+        self.m_trailing: int = 0
+        self.m_hex_strings: bool = hex_strings
+        self.m_strip_padding: bool = strip_padding
+        self.m_tagged_values: bool = tagged_values
+        self.m_lazy: bool = lazy
+        self.m_view_threshold: Optional[int] = view_threshold
+        self.m_ipv6_addresses: bool = ipv6_addresses
+        self.m_errors: Optional[List[DecodeError]] = [] if partial else None
+        self._strip_trailing()
+
+    def __getstate__(self) -> Dict[str, Any]:
+        state = {name: getattr(self, name) for name in self.__slots__}
+        # sub-buffers may be memoryview or bytearray slices of the input
+        state["m_stack"] = [[offset, bytes(data), base] for offset, data, base in self.m_stack]
+        return state
+
+    def __setstate__(self, state: Dict[str, Any]) -> None:
+        for name, value in state.items():
+            setattr(self, name, value)
+
+    def __copy__(self) -> "Decoder":
+        decoder = type(self).__new__(type(self))
+        decoder.__setstate__(self.__getstate__())
+        return decoder
+
+    def __deepcopy__(self, memo: Dict[int, Any]) -> "Decoder":
+        return self.__copy__()
+
+    def reset(self, data: bytes, start: int = 0, length: Optional[int] = None) -> None:
+        """This method discards the current decoding state and starts decoding
+        ``data`` from the beginning, so one decoder can be reused for many
+        messages.
+
+        Note:
+            The decoder keeps a reference to ``data`` until the next reset,
+            which prevents resizing a ``bytearray`` it views. Decoded values
+            never reference the input.
+
+        Args:
+            data (bytes): The new ASN.1 encoded input.
+
+            start (int): Offset of the input within ``data``, e.g. of a
+                datagram received into a ring buffer with ``recv_into``.
+
+            length (int): Number of octets of the input, by default up to
+                the end of ``data``.
+
+        Returns:
+            None
+        """
+        del self.m_stack[1:]
+        self.m_stack[0][0] = 0
+        self.m_stack[0][1] = self._window(data, start, length)
+        self.m_tag = None
+        if self.m_errors is not None:
+            self.m_errors = []
+        self._strip_trailing()
+
+    @property
+    def trailing_bytes(self) -> int:
+        """The number of octets ignored after the top-level tag when the
+        decoder was created with ``ignore_trailing``."""
+        return self.m_trailing
+
+    @property
+    def errors(self) -> List[DecodeError]:
+        """The errors recovered from so far when the decoder was created with
+        ``partial``, in input order."""
+        return list(self.m_errors or ())
+
+    @staticmethod
+    def _window(data: bytes, start: int, length: Optional[int]) -> bytes:
+        """Return a view of ``length`` octets of ``data`` from ``start``."""
+        if start == 0 and length is None:
+            return data
+        end = len(data) if length is None else start + length
+        if not 0 <= start <= end <= len(data):
+            raise ValueError(f"Window of {end - start} octets at {start} exceeds the {len(data)} octets of data")
+        return cast(bytes, memoryview(data)[start:end])
+
+    def _strip_trailing(self) -> None:
+        """Cut the input after the first top-level tag if requested."""
+        self.m_trailing = 0
+        if not self.m_ignore_trailing:
+            return
+        data = self.m_stack[0][1]
+        try:
+            size = _tlv_size(data)
+        except Error:
+            return
+        if size is not None and size < len(data):
+            self.m_trailing = len(data) - size
+            self.m_stack[0][1] = data[:size]
 
     def peek(self) -> Tag:
         """This method returns the current ASN.1 tag (i.e. the tag that a
@@ -352,12 +1007,33 @@ class Decoder:
             `Error`
         """
         if self._end_of_input():
-            raise Error("Input is empty.")
+            raise self._error(PrematureEndError, "Input is empty.")
         if self.m_tag is None:
+            self.m_tag_offset = self.m_stack[-1][0]
             self.m_tag = self._read_tag()
         return self.m_tag
 
-    def read(self, nr: Optional[TNumber] = None) -> Tuple[Tag, Any]:
+    def peek_header(self) -> Header:
+        """This method returns the current ASN.1 tag together with the length
+        of its value and the number of octets occupied by the tag and length
+        encoding, without updating the decoding offset.
+
+        Returns:
+            `Header`: The current ASN.1 tag, its value length and header size.
+
+        Raises:
+            `Error`
+        """
+        tag = self.peek()
+        offset = self.m_stack[-1][0]
+        try:
+            length = self._read_length()
+            size = self.m_stack[-1][0] - self.m_tag_offset
+        finally:
+            self.m_stack[-1][0] = offset
+        return Header(tag, length, size)
+
+    def read(self, nr: Optional[TNumber] = None, *, expect: Union[Tag, TNumber, None] = None) -> Tuple[Tag, Any]:
         """This method decodes one ASN.1 tag from the input and returns it as a
         ``(tag, value)`` tuple. ``tag`` is a 3-tuple ``(nr, typ, cls)``,
         while ``value`` is a Python object representing the ASN.1 value.
@@ -365,22 +1041,307 @@ class Decoder:
         call will return the next tag. In case no more data is available from
         the input, this method returns ``None`` to signal end-of-file.
 
+        Args:
+            nr (int): Decode the value as this ASN.1 type instead of the one
+                of the tag. Use ``Number`` enumeration.
+
+            expect (`Tag` or int): Raise `UnexpectedTagError` without
+                consuming the tag, unless it equals this `Tag` or its number
+                (including the class bits, like ``Number.Counter32``) equals
+                this number.
+
         Returns:
             `Tag`, value: The current ASN.1 tag and its value.
 
         Raises:
             `Error`
         """
+        if self.m_errors is None:
+            return self._read(nr, expect)
+        offset = self.tell()
+        try:
+            return self._read(nr, expect)
+        except DecodeError as exc:
+            return self._recover(exc, offset)
+
+    def _read(self, nr: Optional[TNumber], expect: Union[Tag, TNumber, None]) -> Tuple[Tag, Any]:
+        """Decode one tag, see `Decoder.read()`."""
         if self._end_of_input():
-            raise Error("Input is empty.")
+            raise self._error(PrematureEndError, "Input is empty.")
         tag = self.peek()
+        if expect is not None:
+            self._check_tag(tag, expect)
         length = self._read_length()
+        if nr is None and self.m_lazy:
+            value = self._read_bytes(length)
+            self.m_tag = None
+            return tag, value
+        if nr is None and self._is_tagged_value(tag):
+            value = TaggedValue(tag.cls, tag.nr, self._read_bytes(length), tag.is_constructed)
+            self.m_tag = None
+            return tag, value
         if nr is None:
             nr = tag.nr | tag.cls
-        value = self._read_value(nr, length)
+        try:
+            value = self._read_value(nr, length)
+        except Error as exc:
+            if exc.offset is None:
+                exc.offset = self.m_stack[-1][2] + self.m_tag_offset
+                exc.tag = tag
+                exc.depth = len(self.m_stack) - 1
+            raise
         self.m_tag = None
         return tag, value
 
+    def decode_value(self, tag: Tag, data: bytes) -> Any:
+        """This method converts content octets returned by `Decoder.read()`
+        in lazy mode, with the same options as this decoder.
+
+        Args:
+            tag (`Tag`): The tag the content octets were read for.
+
+            data (bytes): The content octets.
+
+        Returns:
+            any: The decoded value, as `Decoder.read()` would have returned it.
+
+        Raises:
+            `Error`
+        """
+        decoder = Decoder(
+            data,
+            strict=self.m_strict,
+            lenient=self.m_lenient,
+            hex_strings=self.m_hex_strings,
+            strip_padding=self.m_strip_padding,
+            tagged_values=self.m_tagged_values,
+            ipv6_addresses=self.m_ipv6_addresses,
+            view_threshold=self.m_view_threshold,
+        )
+        if self._is_tagged_value(tag):
+            return TaggedValue(tag.cls, tag.nr, bytes(data), tag.is_constructed)
+        return decoder._read_value(tag.nr | tag.cls, len(data))
+
+    def read_integer(self) -> int:
+        """This method decodes an INTEGER, raising `UnexpectedTagError` for
+        any other tag.
+
+        Returns:
+            int: The decoded value.
+
+        Raises:
+            `Error`
+        """
+        return cast(int, self.read(Number.Integer, expect=Number.Integer)[1])
+
+    def read_octet_string(self) -> bytes:
+        """This method decodes an OCTET STRING, raising `UnexpectedTagError`
+        for any other tag. The content octets are returned as they are,
+        regardless of ``hex_strings``, ``strip_padding`` and
+        ``view_threshold``, and errors are raised also with ``partial``.
+
+        Returns:
+            bytes: The decoded value.
+
+        Raises:
+            `Error`
+        """
+        if self._end_of_input():
+            raise self._error(PrematureEndError, "Input is empty.")
+        self._check_tag(self.peek(), Number.OctetString)
+        value = self._read_bytes(self._read_length())
+        self.m_tag = None
+        return value
+
+    def read_oid(self) -> str:
+        """This method decodes an OBJECT IDENTIFIER, raising
+        `UnexpectedTagError` for any other tag.
+
+        Returns:
+            str: The decoded value, like ``.1.3.6.1``.
+
+        Raises:
+            `Error`
+        """
+        return cast(str, self.read(Number.ObjectIdentifier, expect=Number.ObjectIdentifier)[1])
+
+    def read_null(self) -> None:
+        """This method decodes a NULL, raising `UnexpectedTagError` for any
+        other tag.
+
+        Returns:
+            None
+
+        Raises:
+            `Error`
+        """
+        self.read(Number.Null, expect=Number.Null)
+
+    def read_struct(
+        self,
+        schema: Iterable[Field],
+        factory: Optional[Callable[..., Any]] = None,
+        *,
+        expect: Union[Tag, TNumber, None] = Number.Sequence,
+    ) -> Any:
+        """This method decodes a constructed type whose elements are described
+        by ``schema``, one `Field` per element in order.
+
+        Note:
+            An optional field whose tag does not match the current element, or
+            that is missing at the end of the constructed type, gets its
+            ``default`` value without consuming anything. Elements left after
+            the last field are an error.
+
+        Args:
+            schema (iterable): The `Field` descriptions of the elements.
+
+            factory (callable): If given, it is called with the decoded fields
+                as keyword arguments, e.g. a ``NamedTuple`` class.
+
+            expect (`Tag` or int): The tag of the constructed type itself, None
+                accepts any constructed tag.
+
+        Returns:
+            dict: The decoded values by field name, or the ``factory`` result.
+
+        Raises:
+            `UnexpectedTagError` naming the field whose tag does not match,
+            `Error`
+        """
+        tag = self.peek()
+        if expect is not None:
+            self._check_tag(tag, expect)
+        values: Dict[str, Any] = {}
+        with self.enter():
+            for field in schema:
+                if self._end_of_input():
+                    if not field.optional:
+                        raise self._error(PrematureEndError, f"Field {field.name!r} is missing.")
+                    values[field.name] = field.default
+                    continue
+                tag = self.peek()
+                if field.expect is not None and not self._tag_matches(tag, field.expect):
+                    if not field.optional:
+                        self._check_tag(tag, field.expect, field.name)
+                    values[field.name] = field.default
+                elif field.schema is not None:
+                    values[field.name] = self.read_struct(field.schema, expect=None)
+                else:
+                    values[field.name] = self.read()[1]
+            if not self._end_of_input():
+                raise self._error(DecodeError, f"Unexpected {self.peek()!r} after the last field.")
+        return factory(**values) if factory is not None else values
+
+    def skip(self) -> Tag:
+        """This method advances past the current ASN.1 tag, primitive or
+        constructed, without decoding its value.
+
+        Returns:
+            `Tag`: The skipped ASN.1 tag.
+
+        Raises:
+            `Error`
+        """
+        if self._end_of_input():
+            raise self._error(PrematureEndError, "Input is empty.")
+        tag = self.peek()
+        length = self._read_length()
+        self._read_bytes(length)
+        self.m_tag = None
+        return tag
+
+    def read_raw(self) -> bytes:
+        """This method returns the complete encoding (tag, length and value
+        octets) of the current ASN.1 tag and advances past it.
+
+        Returns:
+            bytes: The raw encoded tag.
+
+        Raises:
+            `Error`
+        """
+        if self._end_of_input():
+            raise self._error(PrematureEndError, "Input is empty.")
+        self.peek()
+        start = self.m_tag_offset
+        length = self._read_length()
+        self._read_bytes(length)
+        self.m_tag = None
+        index, input_data, _ = self.m_stack[-1]
+        return bytes(input_data[start:index])
+
+    def read_all(self) -> List[Tuple[Tag, Any]]:
+        """This method decodes all remaining input of the current constructed
+        type (or of the whole input at the top level). Constructed types are
+        entered recursively and represented as a list of their children.
+
+        Returns:
+            list: ``(tag, value)`` tuples, where ``value`` of a constructed
+            tag is again a list of ``(tag, value)`` tuples.
+
+        Raises:
+            `Error`
+        """
+        result: List[Tuple[Tag, Any]] = []
+        while not self.eof():
+            if self.m_errors is None:
+                result.append(self._read_element())
+                continue
+            offset = self.tell()
+            try:
+                result.append(self._read_element())
+            except DecodeError as exc:
+                result.append(self._recover(exc, offset))
+        return result
+
+    def _read_element(self) -> Tuple[Tag, Any]:
+        """Decode the current tag, entering constructed types recursively."""
+        tag = self.peek()
+        if tag.typ == Type.Constructed:
+            with self.enter():
+                return tag, self.read_all()
+        return self.read()
+
+    def recover(self, error: DecodeError, offset: int) -> Tuple[Tag, Malformed]:
+        """This method handles ``error`` of the element at ``offset`` like
+        `Decoder.read()` does in partial mode, e.g. for an error raised by
+        `Decoder.enter()`. Without ``partial`` the error is raised.
+
+        Args:
+            error (`DecodeError`): The error of the element.
+
+            offset (int): The element's offset as returned by `Decoder.tell()`
+                before decoding it.
+
+        Returns:
+            tuple: The element's tag and a `Malformed` placeholder.
+
+        Raises:
+            `Error`
+        """
+        if self.m_errors is None:
+            raise error
+        return self._recover(error, offset)
+
+    def _recover(self, error: DecodeError, offset: int) -> Tuple[Tag, Malformed]:
+        """Record ``error`` of the element at ``offset`` and move past it."""
+        assert self.m_errors is not None
+        self.m_errors.append(error)
+        self.m_stack[-1][0] = offset
+        self.m_tag = None
+        try:
+            tag = self.peek()
+            data = self._read_bytes(self._read_length())
+        except DecodeError:
+            # without a length there is no next element to continue at
+            input_data = self.m_stack[-1][1]
+            tag = error.tag or Tag(Number.EndOfContents, Type.Primitive, Class.Universal)
+            data = bytes(input_data[offset:])
+            self.m_stack[-1][0] = len(input_data)
+        self.m_tag = None
+        return tag, Malformed(data, error)
+
     def eof(self) -> bool:
         """Return True if we are at the end of input.
 
@@ -389,6 +1350,59 @@ class Decoder:
         """
         return self._end_of_input()
 
+    def tell(self) -> int:
+        """This method returns the decoding offset within the constructed type
+        that is currently being decoded (or within the whole input at the top
+        level). A peeked but not yet read tag is not counted as consumed.
+
+        Returns:
+            int: The current offset.
+        """
+        if self.m_tag is not None:
+            return self.m_tag_offset
+        return cast(int, self.m_stack[-1][0])
+
+    def hexdump(self, limit: int = 64) -> str:
+        """This method renders the input at and after the current offset as
+        hex and ASCII, 16 octets per line, e.g. to log the bytes a decoding
+        error was raised for. Lines start with the absolute input offset.
+
+        Args:
+            limit (int): The maximum number of octets to render.
+
+        Returns:
+            str: The formatted dump, empty at the end of input.
+        """
+        start = self.tell()
+        data = bytes(self.m_stack[-1][1][start : start + limit])
+        base = self.m_stack[-1][2] + start
+        lines = []
+        for i in range(0, len(data), 16):
+            chunk = data[i : i + 16]
+            octets = " ".join(f"{byte:02x}" for byte in chunk)
+            text = "".join(chr(byte) if 0x20 <= byte < 0x7F else "." for byte in chunk)
+            lines.append(f"{base + i:08x}  {octets:<47}  |{text}|")
+        return "\n".join(lines)
+
+    def seek(self, offset: int) -> None:
+        """This method moves the decoding offset within the constructed type
+        that is currently being decoded, e.g. to a value returned earlier by
+        `Decoder.tell()`.
+
+        Args:
+            offset (int): The new offset.
+
+        Returns:
+            None
+
+        Raises:
+            `Error`
+        """
+        if not 0 <= offset <= len(self.m_stack[-1][1]):
+            raise self._error(DecodeError, f"Offset {offset} is out of bounds.")
+        self.m_stack[-1][0] = offset
+        self.m_tag = None
+
     @contextmanager
     def enter(self) -> Iterator[None]:
         """This method enters the constructed type that is at the current
@@ -396,25 +1410,66 @@ class Decoder:
 
         Note:
             It is an error to call `Decoder.enter()` if the to be decoded ASN.1 tag
-            is not of a constructed type.
+            is not of a constructed type. The constructed type is left when the
+            ``with`` block exits, also when it exits with an exception.
 
         Returns:
             None
         """
         tag = self.peek()
         if tag.typ != Type.Constructed:
-            raise Error("Cannot enter a non-constructed tag.")
+            raise self._error(DecodeError, "Cannot enter a non-constructed tag.")
+        if self.m_max_depth is not None and len(self.m_stack) > self.m_max_depth:
+            raise self._error(LimitError, f"Nesting depth exceeds the limit of {self.m_max_depth}.")
         length = self._read_length()
-        bytes_data = self._read_bytes(length)
-        self.m_stack.append([0, bytes_data])
+        index, input_data, base = self.m_stack[-1]
+        # a view into the parent input, the constructed value is not copied
+        view = memoryview(input_data)[index : index + length]
+        self.m_stack[-1][0] += length
+        self.m_stack.append([0, view, base + index])
         self.m_tag = None
 
-        yield
+        try:
+            yield
+        finally:
+            # leave the constructed type even if decoding inside of it failed
+            if len(self.m_stack) == 1:
+                raise self._error(DecodeError, "Tag stack is empty.")
+            del self.m_stack[-1]
+            self.m_tag = None
+
+    def _is_tagged_value(self, tag: Tag) -> bool:
+        """Return True if the value of ``tag`` is returned as `TaggedValue`."""
+        return self.m_tagged_values and tag.cls != Class.Universal and tag.nr | tag.cls not in _NUMBERS
 
-        if len(self.m_stack) == 1:
-            raise Error("Tag stack is empty.")
-        del self.m_stack[-1]
-        self.m_tag = None
+    @staticmethod
+    def _tag_matches(tag: Tag, expect: Union[Tag, TNumber]) -> bool:
+        """Return True if ``tag`` equals ``expect`` or has its number."""
+        if isinstance(expect, Tag):
+            return tag == expect
+        return tag.nr | tag.cls == expect
+
+    def _check_tag(self, tag: Tag, expect: Union[Tag, TNumber], field: Optional[str] = None) -> None:
+        """Raise an error if ``tag`` is not the expected one, naming ``field`` if given."""
+        if self._tag_matches(tag, expect):
+            return
+        if isinstance(expect, Tag):
+            expected = repr(expect)
+        else:
+            try:
+                expected = Number(expect).name
+            except ValueError:
+                expected = hex(expect)
+        prefix = f"Field {field!r}: " if field is not None else ""
+        raise self._error(UnexpectedTagError, f"{prefix}Expected {expected}, got {tag!r}.", self.m_tag_offset)
+
+    def _error(self, cls: Callable[..., Error], message: str, offset: Optional[int] = None) -> Error:
+        """Create an error at ``offset`` within the current constructed type,
+        by default at the current decoding offset."""
+        index, _, base = self.m_stack[-1]
+        if offset is None:
+            offset = index
+        return cls(message, offset=base + offset, tag=self.m_tag, depth=len(self.m_stack) - 1)
 
     def _read_tag(self) -> Tag:
         """Read a tag from the input."""
@@ -437,23 +1492,36 @@ class Decoder:
         if byte & 0x80:
             count = byte & 0x7F
             if count == 0x7F:
-                raise Error("ASN1 syntax error")
+                raise self._error(Asn1SyntaxError, "ASN1 syntax error")
+            if count == 0:
+                # SNMP uses definite lengths only, as do `message_boundaries()` and `split_messages()`
+                raise self._error(DecodeError, "Indefinite length is not supported.")
+            if count > 8:
+                raise self._error(Asn1SyntaxError, f"Length of {count} octets does not fit 64 bits.")
             bytes_data = self._read_bytes(count)
             length = 0
             for byte in bytes_data:
                 length = (length << 8) | int(byte)
-            try:
-                length = int(length)
-            except OverflowError:
-                pass
+            if self.m_strict and (bytes_data[0] == 0 or length < 0x80):
+                raise self._error(Asn1SyntaxError, "Non-minimal length encoding.", self.m_tag_offset)
         else:
             length = byte
+        if self.m_max_length is not None and length > self.m_max_length:
+            raise self._error(LimitError, f"Length {length} exceeds the limit of {self.m_max_length}.")
+        index, input_data, _ = self.m_stack[-1]
+        remaining = len(input_data) - index
+        if length > remaining:
+            raise self._error(PrematureEndError, f"Length {length} exceeds the remaining {remaining} octets of input.")
         return length
 
     def _read_value(self, nr: TNumber, length: int) -> Any:
         """Read a value from the input."""
+        if self._is_view(nr, length):
+            return OctetView(self._read_view(length))
         bytes_data = self._read_bytes(length)
         if nr == Number.Boolean:
+            if self.m_strict and len(bytes_data) == 1 and bytes_data[0] not in (0x00, 0xFF):
+                raise self._error(Asn1SyntaxError, "Boolean true should be encoded as 0xFF.", self.m_tag_offset)
             return self._decode_boolean(bytes_data)
         elif nr in (
             Number.Integer,
@@ -464,15 +1532,27 @@ class Decoder:
             Number.Counter64,
             Number.Uinteger32,
         ):
+            if self.m_strict and len(bytes_data) > 1 and bytes_data[0] in (0x00, 0xFF):
+                # the first octet only repeats the sign of the next one
+                if bytes_data[0] & 0x80 == bytes_data[1] & 0x80:
+                    raise self._error(Asn1SyntaxError, "Non-minimal integer encoding.", self.m_tag_offset)
+            if self.m_lenient and not bytes_data:
+                return 0
             return self._decode_integer(bytes_data)
         elif nr == Number.OctetString:
-            return self._decode_octet_string(bytes_data)
+            return self._octet_string_value(bytes_data)
         elif nr == Number.Null:
+            if self.m_lenient:
+                return None
             return self._decode_null(bytes_data)
         elif nr == Number.ObjectIdentifier:
-            return self._decode_object_identifier(bytes_data)
+            return self._decode_object_identifier(bytes_data, self.m_lenient)
         elif nr in (Number.PrintableString, Number.IA5String, Number.UTCTime):
-            return self._decode_printable_string(bytes_data)
+            return self._strip(self._decode_printable_string(bytes_data))
+        elif nr in (Number.UTF8String, Number.NumericString, Number.VisibleString):
+            return self._strip(self._decode_string(bytes_data, "utf-8"))
+        elif nr == Number.BMPString:
+            return self._strip(self._decode_string(bytes_data, "utf-16-be"))
         elif nr in (Number.EndOfMibView, Number.NoSuchObject, Number.NoSuchInstance):
             return None
         elif nr == Number.IPAddress:
@@ -481,60 +1561,83 @@ class Decoder:
 
     def _read_byte(self) -> int:
         """Return the next input byte, or raise an error on end-of-input."""
-        index, input_data = self.m_stack[-1]
+        index, input_data, _ = self.m_stack[-1]
         try:
             byte: int = input_data[index]
         except IndexError:
-            raise Error("Premature end of input.")
+            raise self._error(PrematureEndError, "Premature end of input.")
         self.m_stack[-1][0] += 1
         return byte
 
     def _read_bytes(self, count: int) -> bytes:
         """Return the next ``count`` bytes of input. Raise error on
         end-of-input."""
-        index, input_data = self.m_stack[-1]
-        bytes_data: bytes = input_data[index : index + count]
+        index, input_data, _ = self.m_stack[-1]
+        bytes_data = input_data[index : index + count]
         if len(bytes_data) != count:
-            raise Error("Premature end of input.")
+            raise self._error(PrematureEndError, "Premature end of input.")
         self.m_stack[-1][0] += count
+        # values never reference a bytearray or memoryview input
+        if not isinstance(bytes_data, bytes):
+            return bytes(bytes_data)
         return bytes_data
 
+    def _is_view(self, nr: TNumber, length: int) -> bool:
+        """Return True if the value is returned as `OctetView`."""
+        if self.m_view_threshold is None or length < self.m_view_threshold:
+            return False
+        return nr in (Number.OctetString, Number.Opaque)
+
+    def _read_view(self, count: int) -> memoryview:
+        """Return the next ``count`` bytes of input without copying them."""
+        index, input_data, _ = self.m_stack[-1]
+        if len(input_data) - index < count:
+            raise self._error(PrematureEndError, "Premature end of input.")
+        self.m_stack[-1][0] += count
+        return memoryview(input_data)[index : index + count]
+
     def _end_of_input(self) -> bool:
         """Return True if we are at the end of input."""
-        index, input_data = self.m_stack[-1]
+        index, input_data, _ = self.m_stack[-1]
         assert not index > len(input_data)
         return cast(int, index) == len(input_data)
 
     @staticmethod
     def _decode_boolean(bytes_data: bytes) -> bool:
         if len(bytes_data) != 1:
-            raise Error("ASN1 syntax error")
+            raise Asn1SyntaxError("ASN1 syntax error")
         return not bytes_data[0] == 0
 
     @staticmethod
     def _decode_integer(bytes_data: bytes) -> int:
-        values = [int(b) for b in bytes_data]
-        negative = values[0] & 0x80
-        if negative:
-            # make positive by taking two's complement
-            for i in range(len(values)):
-                values[i] = 0xFF - values[i]
-            for i in range(len(values) - 1, -1, -1):
-                values[i] += 1
-                if values[i] <= 0xFF:
-                    break
-                assert i > 0
-                values[i] = 0x00
-        value = 0
-        for val in values:
-            value = (value << 8) | val
-        if negative:
-            value = -value
-        try:
-            value = int(value)
-        except OverflowError:
-            pass
-        return value
+        if not bytes_data:
+            raise Asn1SyntaxError("ASN1 syntax error, integer has no content octets.")
+        return int.from_bytes(bytes_data, byteorder="big", signed=True)
+
+    def _octet_string_value(self, bytes_data: bytes) -> Union[bytes, str]:
+        """Decode an octet string according to the decoder options."""
+        if self.m_strip_padding:
+            stripped = bytes_data.rstrip(b"\x00 ")
+            if stripped and self._is_printable(stripped):
+                bytes_data = stripped
+        if self.m_hex_strings:
+            return self._decode_hex_string(bytes_data)
+        return self._decode_octet_string(bytes_data)
+
+    def _strip(self, value: str) -> str:
+        """Remove trailing padding from a character string if enabled."""
+        return value.rstrip("\x00 ") if self.m_strip_padding else value
+
+    @staticmethod
+    def _is_printable(bytes_data: bytes) -> bool:
+        return all(0x20 <= byte < 0x7F or byte in b"\t\n\r" for byte in bytes_data)
+
+    @classmethod
+    def _decode_hex_string(cls, bytes_data: bytes) -> str:
+        """Decode an octet string as text if printable, as hex otherwise."""
+        if cls._is_printable(bytes_data):
+            return bytes(bytes_data).decode("ascii")
+        return f"0x{bytes_data.hex().upper()}"
 
     @staticmethod
     def _decode_octet_string(bytes_data: bytes) -> bytes:
@@ -543,29 +1646,367 @@ class Decoder:
     @staticmethod
     def _decode_null(bytes_data: bytes) -> None:
         if len(bytes_data) != 0:
-            raise Error("ASN1 syntax error")
+            raise Asn1SyntaxError("ASN1 syntax error")
 
     @staticmethod
-    def _decode_object_identifier(bytes_data: bytes) -> str:
+    def _decode_object_identifier(bytes_data: bytes, lenient: bool = False) -> str:
         result: List[int] = []
         value: int = 0
         for i in range(len(bytes_data)):
             byte = int(bytes_data[i])
             if value == 0 and byte == 0x80:
-                raise Error("ASN1 syntax error")
+                raise Asn1SyntaxError("ASN1 syntax error")
             value = (value << 7) | (byte & 0x7F)
             if not byte & 0x80:
                 result.append(value)
                 value = 0
-        if len(result) == 0 or result[0] > 1599:
-            raise Error("ASN1 syntax error")
-        result = [result[0] // 40, result[0] % 40] + result[1:]
+        if lenient and len(result) == 0:
+            return ""
+        if lenient and result[0] >= 80:
+            result = [2, result[0] - 80] + result[1:]
+        elif len(result) == 0 or result[0] > 1599:
+            raise Asn1SyntaxError("ASN1 syntax error")
+        else:
+            result = [result[0] // 40, result[0] % 40] + result[1:]
         return f".{'.'.join(str(x) for x in result)}"
 
     @staticmethod
     def _decode_printable_string(bytes_data: bytes) -> str:
         return bytes_data.decode("utf-8")
 
-    @staticmethod
-    def _decode_ip_address(bytes_data: bytes) -> ipaddress.IPv4Address:
-        return ipaddress.IPv4Address(int.from_bytes(bytes_data, byteorder="big"))
+    def _decode_string(self, bytes_data: bytes, encoding: str) -> str:
+        try:
+            return bytes(bytes_data).decode(encoding)
+        except UnicodeDecodeError as exc:
+            raise self._error(Asn1SyntaxError, f"Invalid {encoding} string: {exc.reason}.")
+
+    def _decode_ip_address(self, bytes_data: bytes) -> Union[ipaddress.IPv4Address, ipaddress.IPv6Address]:
+        if len(bytes_data) == 4:
+            return ipaddress.IPv4Address(bytes(bytes_data))
+        if len(bytes_data) == 16 and self.m_ipv6_addresses:
+            return ipaddress.IPv6Address(bytes(bytes_data))
+        raise self._error(Asn1SyntaxError, f"IpAddress should be 4 octets, got {len(bytes_data)}.")
+
+
+def _tlv_header(data: Union[bytes, bytearray, memoryview], offset: int = 0) -> Optional[Tuple[int, int]]:
+    """Return the size of the tag and length octets and the value length
+    of the tag starting at ``offset``, or None if ``data`` does not contain
+    all of its header yet."""
+    end = len(data)
+    pos = offset + 1
+    if pos > end:
+        return None
+    if data[offset] & 0x1F == 0x1F:  # Long form of tag encoding
+        while True:
+            if pos >= end:
+                return None
+            pos += 1
+            if not data[pos - 1] & 0x80:
+                break
+    if pos >= end:
+        return None
+    byte = data[pos]
+    pos += 1
+    if byte & 0x80:
+        count = byte & 0x7F
+        if count == 0x7F:
+            raise Asn1SyntaxError("ASN1 syntax error", offset=offset)
+        if count == 0:
+            raise DecodeError("Indefinite length is not supported.", offset=offset)
+        if pos + count > end:
+            return None
+        length = int.from_bytes(data[pos : pos + count], byteorder="big")
+        pos += count
+    else:
+        length = byte
+    return pos - offset, length
+
+
+def _tlv_size(data: Union[bytes, bytearray, memoryview], offset: int = 0) -> Optional[int]:
+    """Return the encoded size of the tag starting at ``offset``, or None
+    if ``data`` does not contain all of it yet."""
+    header = _tlv_header(data, offset)
+    if header is None or offset + header[0] + header[1] > len(data):
+        return None
+    return header[0] + header[1]
+
+
+def message_boundaries(data: Union[bytes, bytearray, memoryview]) -> List[Tuple[int, int]]:
+    """This function locates the complete top-level ASN.1 tags at the start
+    of ``data``, e.g. SNMP messages framed over TCP as per RFC 3430, without
+    copying or decoding them.
+
+    Args:
+        data (bytes, bytearray or memoryview): The received octets.
+
+    Returns:
+        list: ``(start, end)`` offsets of every complete tag. The octets after
+        the last ``end`` belong to an incomplete tag.
+
+    Raises:
+        `Error`
+    """
+    boundaries: List[Tuple[int, int]] = []
+    offset = 0
+    while True:
+        size = _tlv_size(data, offset)
+        if size is None:
+            return boundaries
+        boundaries.append((offset, offset + size))
+        offset += size
+
+
+def split_messages(data: bytes) -> List[bytes]:
+    """This function splits a buffer holding several back-to-back top-level
+    ASN.1 tags into the encoding of each of them.
+
+    Args:
+        data (bytes): Concatenated complete tags.
+
+    Returns:
+        list: The encoded tags, in order.
+
+    Raises:
+        `Error`
+    """
+    boundaries = message_boundaries(data)
+    if (boundaries[-1][1] if boundaries else 0) != len(data):
+        raise PrematureEndError("Premature end of input.", offset=len(data))
+    return [data[start:end] for start, end in boundaries]
+
+
+def decode(data: bytes) -> List[Tuple[Tag, Any]]:
+    """This function decodes a whole buffer into a tree of tags, see
+    `Decoder.read_all()`.
+
+    Args:
+        data (bytes): ASN.1 encoded data.
+
+    Returns:
+        list: ``(tag, value)`` tuples, where ``value`` of a constructed
+        tag is again a list of ``(tag, value)`` tuples.
+
+    Raises:
+        `Error`
+    """
+    return Decoder(data).read_all()
+
+
+def encode(tree: Iterable[Tuple[Tag, Any]]) -> bytes:
+    """This function encodes a tree of tags as returned by `decode()`.
+    Primitive values are encoded according to their Python type, so
+    ``encode(decode(data))`` reproduces ``data`` for DER input.
+
+    Args:
+        tree (iterable): ``(tag, value)`` tuples, where ``value`` of a
+            constructed tag is again an iterable of ``(tag, value)`` tuples.
+
+    Returns:
+        bytes: The encoded data.
+
+    Raises:
+        `Error`
+    """
+    encoder = Encoder()
+    _encode_tree(encoder, tree)
+    return encoder.output()
+
+
+def _encode_tree(encoder: Encoder, tree: Iterable[Tuple[Tag, Any]]) -> None:
+    for tag, value in tree:
+        if tag.typ == Type.Constructed:
+            with encoder.enter(tag.nr, tag.cls):
+                _encode_tree(encoder, value)
+            continue
+
+        content: bytes
+        if value is None:
+            content = b""
+        elif isinstance(value, TaggedValue):
+            content = value.data
+        elif isinstance(value, bytes):
+            content = value
+        elif isinstance(value, OctetView):
+            content = value.to_bytes()
+        elif isinstance(value, bool):
+            content = encoder._encode_boolean(value)
+        elif isinstance(value, int):
+            # two's complement as decoded, also for unsigned types agents sent without the leading zero
+            content = encoder._encode_integer(value)
+        elif isinstance(value, (ipaddress.IPv4Address, ipaddress.IPv6Address)):
+            content = value.packed
+        elif isinstance(value, str) and tag.cls == Class.Universal:
+            # the character encoding depends on the string type, e.g. UTF-16 for BMPString
+            content = encoder._encode_value(tag.nr, value.lstrip(".") if tag.nr == Number.ObjectIdentifier else value)
+        elif isinstance(value, str):
+            content = encoder._encode_octet_string(value)
+        else:
+            raise EncodeError(f"Cannot encode value type {type(value)}", tag=tag)
+
+        if tag.nr < 0x1F:
+            encoder._emit_tag_short(tag.nr, tag.typ, tag.cls)
+        else:
+            encoder._emit_tag_long(tag.nr, tag.typ, tag.cls)
+        encoder._emit_length(len(content))
+        encoder._emit(content)
+
+
+def dump(data: bytes) -> str:
+    """This function renders an indented, offset annotated breakdown of ASN.1
+    encoded data similar to ``openssl asn1parse``, e.g.::
+
+            0:d=0  hl=2 l=  38 cons: Sequence
+            2:d=1  hl=2 l=   1 prim:  Integer            :1
+
+    Args:
+        data (bytes): ASN.1 encoded data.
+
+    Returns:
+        str: One line per tag.
+
+    Raises:
+        `Error`
+    """
+    decoder = Decoder(data)
+    lines: List[str] = []
+    _dump(decoder, lines)
+    return "\n".join(lines)
+
+
+class Mismatch(NamedTuple):
+    offset: int
+    expected: bytes
+    actual: bytes
+    context: str
+
+
+def verify_roundtrip(
+    data: bytes,
+    decode: Callable[[bytes], Any] = decode,
+    encode: Callable[[Any], bytes] = encode,
+) -> Optional[Mismatch]:
+    """This function decodes ``data``, encodes the result again and compares
+    it with ``data``, e.g. to check which octets an encoder changes when an
+    agent disagrees with its output.
+
+    Args:
+        data (bytes): ASN.1 encoded data.
+
+        decode (callable): The decoding function, `decode()` by default.
+
+        encode (callable): The encoding function for the decoded value,
+            `encode()` by default.
+
+    Returns:
+        `Mismatch`: ``None`` if the encoding is reproduced, otherwise the
+        offset of the first differing octet, up to 16 octets of both
+        encodings from there, and the `dump()` line of the last tag that
+        starts at or before the offset.
+
+    Raises:
+        `Error`
+    """
+    data = bytes(data)
+    output = encode(decode(data))
+    if output == data:
+        return None
+    offset = next((i for i, (a, b) in enumerate(zip(data, output)) if a != b), min(len(data), len(output)))
+    context = ""
+    for line in dump(data).splitlines():
+        if int(line.split(":", 1)[0]) > offset:
+            break
+        context = line
+    return Mismatch(offset, data[offset : offset + 16], output[offset : offset + 16], context)
+
+
+def _tag_name(tag: Tag) -> str:
+    if tag.nr < 0x1F and tag.cls in (Class.Universal, Class.Application):
+        if tag.nr | tag.cls in Number.__members__.values():
+            return Number(tag.nr | tag.cls).name
+    return f"{tag.class_name} [ {tag.nr} ]"
+
+
+def _dump_value(raw: bytes) -> str:
+    try:
+        _, value = Decoder(raw).read()
+    except DecodeError:
+        return f"<invalid> {raw.hex().upper()}"
+    if value is None:
+        return ""
+    if isinstance(value, bytes):
+        if all(0x20 <= byte < 0x7F for byte in value):
+            return value.decode()
+        return value.hex().upper()
+    return str(value)
+
+
+def _dump(decoder: Decoder, lines: List[str]) -> None:
+    depth = len(decoder.m_stack) - 1
+    while not decoder.eof():
+        offset = decoder.m_stack[-1][2] + decoder.tell()
+        header = decoder.peek_header()
+        line = f"{offset:5d}:d={depth:<2d} hl={header.size} l={header.length:4d}"
+        name = " " * depth + _tag_name(header.tag)
+        if header.tag.is_constructed:
+            lines.append(f"{line} cons: {name}")
+            with decoder.enter():
+                _dump(decoder, lines)
+        else:
+            lines.append(f"{line} prim: {name:<20}:{_dump_value(decoder.read_raw())}")
+
+
+class StreamDecoder:
+    __slots__ = ("m_buffer", "m_max_message_size")
+
+    def __init__(self, *, max_message_size: Optional[int] = None) -> None:
+        """
+        Args:
+            max_message_size (int): Optional limit of a top-level tag's
+                encoded size in octets. `LimitError` is raised as soon as a
+                larger length is received, instead of buffering the tag.
+        """
+        self.m_buffer: bytearray = bytearray()
+        self.m_max_message_size: Optional[int] = max_message_size
+
+    def feed(self, data: bytes) -> List[bytes]:
+        """This method appends ``data`` received from a stream transport to
+        the internal buffer and returns every top-level ASN.1 tag that is
+        complete now. Incomplete data is kept until more is fed.
+
+        Args:
+            data (bytes): The next chunk of the stream.
+
+        Returns:
+            list: Complete encoded top-level tags, in stream order. Use
+            `Decoder` to decode each of them.
+
+        Raises:
+            `Error`: The framing of the stream is lost. The octets from the
+            malformed tag on are dropped, tags completed before it are kept
+            and returned by the next call, e.g. ``feed(b"")``.
+        """
+        self.m_buffer += data
+        messages: List[bytes] = []
+        offset = 0
+        try:
+            while True:
+                header = _tlv_header(self.m_buffer, offset)
+                if header is None:
+                    break
+                size = header[0] + header[1]
+                if self.m_max_message_size is not None and size > self.m_max_message_size:
+                    raise LimitError(f"Size {size} exceeds the limit of {self.m_max_message_size}.", offset=offset)
+                if offset + size > len(self.m_buffer):
+                    break
+                messages.append(bytes(self.m_buffer[offset : offset + size]))
+                offset += size
+        except Error:
+            del self.m_buffer[offset:]
+            raise
+        del self.m_buffer[:offset]
+        return messages
+
+    @property
+    def pending(self) -> int:
+        """The number of buffered octets that do not form a complete tag yet."""
+        return len(self.m_buffer)
@@ -0,0 +1,199 @@
+__all__ = ("EngineIdFormat", "EngineId", "EngineTimeCache", "generate_engine_id", "parse_engine_id")
+
+import enum
+import ipaddress
+import time
+from typing import Callable, Dict, Optional, Tuple, Union
+
+from .exceptions import SnmpInvalidEngineId
+
+ENGINE_ID_MIN_LENGTH = 5
+ENGINE_ID_MAX_LENGTH = 32
+# RFC 3411 caps text and octets formats at 27 octets, 5 octets are taken by the header
+_MAX_VARIABLE_LENGTH = 27
+
+TIME_WINDOW = 150
+MAX_ENGINE_BOOTS = 2_147_483_647
+MAX_ENGINE_TIME = 2_147_483_647
+
+EngineIdValue = Union[ipaddress.IPv4Address, ipaddress.IPv6Address, str, bytes]
+
+
+class EngineIdFormat(enum.IntEnum):
+    IPv4 = 0x01
+    IPv6 = 0x02
+    MAC = 0x03
+    Text = 0x04
+    Octets = 0x05
+
+
+class EngineId:
+    __slots__ = ("raw", "enterprise", "format", "value")
+
+    def __init__(self, raw: bytes, enterprise: int, fmt: Optional[int], value: EngineIdValue) -> None:
+        self.raw: bytes = raw
+        self.enterprise: int = enterprise
+        # None means the engine id uses the pre RFC 3411 (SNMPv1/v2c era) 12 octets layout
+        self.format: Optional[int] = fmt
+        self.value: EngineIdValue = value
+
+    @property
+    def format_name(self) -> str:
+        if self.format is None:
+            return "legacy"
+        if self.format >= 128:
+            return "enterprise"
+        try:
+            return EngineIdFormat(self.format).name
+        except ValueError:
+            return "reserved"
+
+    def __str__(self) -> str:
+        value = self.value.hex() if isinstance(self.value, bytes) else str(self.value)
+        return f"{self.enterprise}:{self.format_name}:{value}"
+
+    def __repr__(self) -> str:
+        return f"<EngineId enterprise={self.enterprise} format={self.format_name} value={self.value!r}>"
+
+
+def _mac_to_bytes(value: Union[str, bytes]) -> bytes:
+    if isinstance(value, bytes):
+        mac = value
+    else:
+        try:
+            mac = bytes.fromhex(value.replace(":", "").replace("-", ""))
+        except ValueError:
+            raise SnmpInvalidEngineId(f"Invalid mac address {value!r}")
+    if len(mac) != 6:
+        raise SnmpInvalidEngineId(f"Mac address should be 6 octets, got {len(mac)}")
+    return mac
+
+
+def generate_engine_id(
+    value: EngineIdValue,
+    *,
+    enterprise: int,
+    fmt: Union[EngineIdFormat, int, None] = None,
+) -> bytes:
+    if not 0 <= enterprise < 2 ** 31:
+        raise SnmpInvalidEngineId(f"Enterprise number should fit 31 bits, got {enterprise}")
+
+    if fmt is None:
+        if isinstance(value, ipaddress.IPv4Address):
+            fmt = EngineIdFormat.IPv4
+        elif isinstance(value, ipaddress.IPv6Address):
+            fmt = EngineIdFormat.IPv6
+        elif isinstance(value, str):
+            fmt = EngineIdFormat.Text
+        elif isinstance(value, bytes):
+            fmt = EngineIdFormat.Octets
+        else:
+            raise SnmpInvalidEngineId(f"Cannot determine engine id format for value type {type(value)}")
+
+    data: bytes
+    if fmt == EngineIdFormat.IPv4:
+        data = ipaddress.IPv4Address(value).packed
+    elif fmt == EngineIdFormat.IPv6:
+        data = ipaddress.IPv6Address(value).packed
+    elif fmt == EngineIdFormat.MAC:
+        if not isinstance(value, (str, bytes)):
+            raise SnmpInvalidEngineId(f"Mac address should be str or bytes, got {type(value)}")
+        data = _mac_to_bytes(value)
+    elif fmt == EngineIdFormat.Text:
+        if not isinstance(value, str):
+            raise SnmpInvalidEngineId(f"Text format requires str, got {type(value)}")
+        data = value.encode("ascii")
+    elif fmt == EngineIdFormat.Octets or 128 <= fmt <= 255:
+        if not isinstance(value, bytes):
+            raise SnmpInvalidEngineId(f"Octets format requires bytes, got {type(value)}")
+        data = value
+    else:
+        raise SnmpInvalidEngineId(f"Reserved engine id format {fmt}")
+
+    if not data or len(data) > _MAX_VARIABLE_LENGTH:
+        raise SnmpInvalidEngineId(f"Engine id value should be 1..{_MAX_VARIABLE_LENGTH} octets, got {len(data)}")
+
+    return (enterprise | 0x80000000).to_bytes(4, byteorder="big") + bytes([fmt]) + data
+
+
+def parse_engine_id(engine_id: bytes) -> EngineId:
+    if not ENGINE_ID_MIN_LENGTH <= len(engine_id) <= ENGINE_ID_MAX_LENGTH:
+        raise SnmpInvalidEngineId(
+            f"Engine id should be {ENGINE_ID_MIN_LENGTH}..{ENGINE_ID_MAX_LENGTH} octets, got {len(engine_id)}"
+        )
+
+    head = int.from_bytes(engine_id[:4], byteorder="big")
+    enterprise = head & 0x7FFFFFFF
+    if not head & 0x80000000:
+        return EngineId(engine_id, enterprise, None, engine_id[4:])
+
+    fmt = engine_id[4]
+    data = engine_id[5:]
+    value: EngineIdValue
+    try:
+        if fmt == EngineIdFormat.IPv4:
+            value = ipaddress.IPv4Address(data)
+        elif fmt == EngineIdFormat.IPv6:
+            value = ipaddress.IPv6Address(data)
+        elif fmt == EngineIdFormat.MAC:
+            value = ":".join(f"{b:02x}" for b in _mac_to_bytes(data))
+        elif fmt == EngineIdFormat.Text:
+            value = data.decode("ascii")
+        else:
+            value = data
+    except (ValueError, SnmpInvalidEngineId) as exc:
+        raise SnmpInvalidEngineId(f"Invalid engine id {engine_id.hex()}: {exc}")
+    return EngineId(engine_id, enterprise, fmt, value)
+
+
+class EngineTimeCache:
+    __slots__ = ("window", "_clock", "_entries")
+
+    def __init__(self, *, window: int = TIME_WINDOW, clock: Callable[[], float] = time.monotonic) -> None:
+        self.window: int = window
+        self._clock: Callable[[], float] = clock
+        # engine id -> (snmpEngineBoots, snmpEngineTime, latestReceivedEngineTime, local timestamp)
+        self._entries: Dict[bytes, Tuple[int, int, int, float]] = {}
+
+    def __contains__(self, engine_id: bytes) -> bool:
+        return engine_id in self._entries
+
+    def __len__(self) -> int:
+        return len(self._entries)
+
+    def update(self, engine_id: bytes, boots: int, engine_time: int) -> bool:
+        entry = self._entries.get(engine_id)
+        if entry is not None:
+            cached_boots, _, latest_received, _ = entry
+            # RFC 3414 3.2.7.b: only newer notion of time replaces the cached one
+            if boots < cached_boots or (boots == cached_boots and engine_time <= latest_received):
+                return False
+        self._entries[engine_id] = (boots, engine_time, engine_time, self._clock())
+        return True
+
+    def get(self, engine_id: bytes) -> Optional[Tuple[int, int]]:
+        entry = self._entries.get(engine_id)
+        if entry is None:
+            return None
+        boots, engine_time, _, timestamp = entry
+        engine_time += int(self._clock() - timestamp)
+        if engine_time > MAX_ENGINE_TIME:
+            return min(boots + 1, MAX_ENGINE_BOOTS), engine_time - MAX_ENGINE_TIME - 1
+        return boots, engine_time
+
+    def is_in_time_window(self, engine_id: bytes, boots: int, engine_time: int) -> bool:
+        current = self.get(engine_id)
+        if current is None:
+            return False
+        cached_boots, cached_time = current
+        if cached_boots >= MAX_ENGINE_BOOTS:
+            return False
+        if boots != cached_boots:
+            return boots > cached_boots
+        return engine_time >= cached_time - self.window
+
+    def remove(self, engine_id: bytes) -> None:
+        self._entries.pop(engine_id, None)
+
+    def clear(self) -> None:
+        self._entries.clear()
@@ -1,6 +1,9 @@
 __all__ = (
     "SnmpTimeoutError",
     "SnmpUnsupportedValueType",
+    "SnmpInvalidEngineId",
+    "SnmpMibError",
+    "SnmpPrivacyParametersReused",
     "SnmpErrorTooBig",
     "SnmpErrorNoSuchName",
     "SnmpErrorBadValue",
@@ -19,6 +22,18 @@ __all__ = (
     "SnmpErrorAuthorizationError",
     "SnmpErrorNotWritable",
     "SnmpErrorInconsistentName",
+    "SnmpReport",
+    "SnmpReportUnsupportedSecLevel",
+    "SnmpReportNotInTimeWindow",
+    "SnmpReportUnknownUserName",
+    "SnmpReportUnknownEngineId",
+    "SnmpReportWrongDigest",
+    "SnmpReportDecryptionError",
+    "SnmpReportUnknownSecurityModel",
+    "SnmpReportInvalidMsg",
+    "SnmpReportUnknownPDUHandler",
+    "SnmpReportUnavailableContext",
+    "SnmpReportUnknownContext",
 )
 
 from asyncio import TimeoutError
@@ -37,6 +52,18 @@ class SnmpUnsupportedValueType(SnmpException):
     pass
 
 
+class SnmpInvalidEngineId(SnmpException):
+    pass
+
+
+class SnmpMibError(SnmpException):
+    pass
+
+
+class SnmpPrivacyParametersReused(SnmpException):
+    pass
+
+
 class SnmpErrorStatus(SnmpException):
     message = ""
 
@@ -134,3 +161,56 @@ class SnmpErrorInconsistentName(SnmpErrorStatus):
         "the agent cannot create it because the named object instance "
         "is inconsistent with the values of other managed objects."
     )
+
+
+class SnmpReport(SnmpException):
+    message = "The agent responded with an unknown report."
+
+    def __init__(self, oid: str, value: Optional[int] = None) -> None:
+        self.oid: str = oid
+        self.value: Optional[int] = value
+        super().__init__(f"oid: {oid}, counter: {value}, message: {self.message}")
+
+
+class SnmpReportUnsupportedSecLevel(SnmpReport):
+    message = "The requested securityLevel was unknown or unavailable."
+
+
+class SnmpReportNotInTimeWindow(SnmpReport):
+    message = "The message appeared outside of the authoritative engine's time window."
+
+
+class SnmpReportUnknownUserName(SnmpReport):
+    message = "The message referenced a user that was not known to the agent."
+
+
+class SnmpReportUnknownEngineId(SnmpReport):
+    message = "The message referenced an snmpEngineID that was not known to the agent."
+
+
+class SnmpReportWrongDigest(SnmpReport):
+    message = "The message did not contain the expected digest value."
+
+
+class SnmpReportDecryptionError(SnmpReport):
+    message = "The message could not be decrypted."
+
+
+class SnmpReportUnknownSecurityModel(SnmpReport):
+    message = "The message referenced a securityModel that was not known to the agent."
+
+
+class SnmpReportInvalidMsg(SnmpReport):
+    message = "The message was dropped because of invalid or inconsistent components."
+
+
+class SnmpReportUnknownPDUHandler(SnmpReport):
+    message = "The PDU contained in the message could not be passed to an application."
+
+
+class SnmpReportUnavailableContext(SnmpReport):
+    message = "The requested context is unavailable."
+
+
+class SnmpReportUnknownContext(SnmpReport):
+    message = "The requested context is unknown."
@@ -1,22 +1,86 @@
 __all__ = (
     "PDU",
     "SnmpVersion",
+    "ErrorStatus",
     "SnmpVarbind",
+    "RequestIdGenerator",
     "SnmpMessage",
     "GetRequest",
     "GetNextRequest",
     "GetBulkRequest",
     "SetRequest",
     "SnmpResponse",
+    "Response",
+    "Report",
+    "InformRequest",
+    "SnmpV1TrapPDU",
+    "SnmpV1TrapMessage",
     "SnmpV2TrapMessage",
+    "encode_get",
+    "encode_getbulk",
+    "encode_inform_response",
+    "decode_response",
+    "parse_trap",
+    "parse_message",
+    "VarbindCallback",
+    "peek_request_id",
+    "peek_pdu_type",
+    "error_status_exception",
+    "decode_many",
+    "decode_many_parallel",
+    "to_dict",
+    "first_outside",
+    "MismatchKind",
+    "VarbindMismatch",
+    "correlate_varbinds",
 )
 
 import enum
+import functools
 import ipaddress
+import os
 import random
-from typing import List, Optional, Union
+import threading
+from concurrent.futures import ProcessPoolExecutor
+from typing import (
+    Any,
+    Callable,
+    Dict,
+    Iterable,
+    Iterator,
+    List,
+    NamedTuple,
+    Optional,
+    Sequence,
+    Tuple,
+    Type,
+    TypeVar,
+    Union,
+)
 
-from .asn1 import Class, Decoder, Encoder, Number
+from .asn1 import Asn1SyntaxError, Class, DecodeError, Decoder, Encoder, Malformed, Number
+from .exceptions import (
+    SnmpErrorAuthorizationError,
+    SnmpErrorBadValue,
+    SnmpErrorCommitFailed,
+    SnmpErrorGenErr,
+    SnmpErrorInconsistentName,
+    SnmpErrorInconsistentValue,
+    SnmpErrorNoAccess,
+    SnmpErrorNoCreation,
+    SnmpErrorNoSuchName,
+    SnmpErrorNotWritable,
+    SnmpErrorReadOnly,
+    SnmpErrorResourceUnavailable,
+    SnmpErrorStatus,
+    SnmpErrorTooBig,
+    SnmpErrorUndoFailed,
+    SnmpErrorWrongEncoding,
+    SnmpErrorWrongLength,
+    SnmpErrorWrongType,
+    SnmpErrorWrongValue,
+)
+from .stats import _stats
 
 
 class SnmpVersion(enum.IntEnum):
@@ -37,16 +101,123 @@ class PDUType(enum.IntEnum):
     Report = 0x08
 
 
+class ErrorStatus(enum.IntEnum):
+    NoError = 0
+    TooBig = 1
+    NoSuchName = 2
+    BadValue = 3
+    ReadOnly = 4
+    GenErr = 5
+    NoAccess = 6
+    WrongType = 7
+    WrongLength = 8
+    WrongEncoding = 9
+    WrongValue = 10
+    NoCreation = 11
+    InconsistentValue = 12
+    ResourceUnavailable = 13
+    CommitFailed = 14
+    UndoFailed = 15
+    AuthorizationError = 16
+    NotWritable = 17
+    InconsistentName = 18
+
+
+_ERROR_STATUS_TO_EXCEPTION: Dict[int, Type[SnmpErrorStatus]] = {
+    ErrorStatus.TooBig: SnmpErrorTooBig,
+    ErrorStatus.NoSuchName: SnmpErrorNoSuchName,
+    ErrorStatus.BadValue: SnmpErrorBadValue,
+    ErrorStatus.ReadOnly: SnmpErrorReadOnly,
+    ErrorStatus.GenErr: SnmpErrorGenErr,
+    ErrorStatus.NoAccess: SnmpErrorNoAccess,
+    ErrorStatus.WrongType: SnmpErrorWrongType,
+    ErrorStatus.WrongLength: SnmpErrorWrongLength,
+    ErrorStatus.WrongEncoding: SnmpErrorWrongEncoding,
+    ErrorStatus.WrongValue: SnmpErrorWrongValue,
+    ErrorStatus.NoCreation: SnmpErrorNoCreation,
+    ErrorStatus.InconsistentValue: SnmpErrorInconsistentValue,
+    ErrorStatus.ResourceUnavailable: SnmpErrorResourceUnavailable,
+    ErrorStatus.CommitFailed: SnmpErrorCommitFailed,
+    ErrorStatus.UndoFailed: SnmpErrorUndoFailed,
+    ErrorStatus.AuthorizationError: SnmpErrorAuthorizationError,
+    ErrorStatus.NotWritable: SnmpErrorNotWritable,
+    ErrorStatus.InconsistentName: SnmpErrorInconsistentName,
+}
+
+MAX_REQUEST_ID = 2_147_483_647
+
+
+class RequestIdGenerator:
+    __slots__ = ("_lock", "_next")
+
+    def __init__(self, start: Optional[int] = None) -> None:
+        if start is None:
+            start = random.randrange(1, MAX_REQUEST_ID)
+        if not 1 <= start <= MAX_REQUEST_ID:
+            raise ValueError(f"Request id should be in 1..{MAX_REQUEST_ID}, got {start}")
+        self._lock: threading.Lock = threading.Lock()
+        self._next: int = start
+
+    def __iter__(self) -> Iterator[int]:
+        return self
+
+    def __next__(self) -> int:
+        with self._lock:
+            value = self._next
+            # ids stay positive 31 bit integers, 0 is skipped on wrap
+            self._next = value % MAX_REQUEST_ID + 1
+        return value
+
+
+_request_ids = RequestIdGenerator()
+
+
+# communities are OCTET STRINGs, binary ones are kept as bytes
+Community = Union[str, bytes]
+
+
+def _decode_community(value: bytes) -> Community:
+    try:
+        return value.decode()
+    except UnicodeDecodeError:
+        return value
+
+
+_T = TypeVar("_T")
+
+
+def _counted(decode: Callable[[bytes], _T], data: bytes) -> _T:
+    if not _stats.enabled:
+        return decode(data)
+    try:
+        message = decode(data)
+    except Exception as exc:
+        _stats.add_error(len(data), exc)
+        raise
+    pdu_type: Optional[PDUType] = getattr(message, "pdu_type", None)
+    _stats.add_decoded(len(data), pdu_type.name if pdu_type is not None else None)
+    return message
+
+
+def _count_encoded(data: bytes, pdu_type: PDUType) -> bytes:
+    if _stats.enabled:
+        _stats.add_encoded(len(data), pdu_type.name)
+    return data
+
+
 class SnmpVarbind:
-    __slots__ = ("_oid", "value")
+    __slots__ = ("_oid", "value", "number")
 
     def __init__(
         self,
         oid: str,
         value: Union[None, str, int, bytes, ipaddress.IPv4Address] = None,
+        number: Optional[int] = None,
     ) -> None:
         self._oid: str = oid.lstrip(".")
         self.value: Union[None, str, int, bytes, ipaddress.IPv4Address] = value
+        # ASN.1 type of a decoded value, e.g. to tell Counter32 from Gauge32
+        self.number: Optional[int] = number
 
     @property
     def oid(self) -> str:
@@ -55,7 +226,11 @@ class SnmpVarbind:
     def encode(self, encoder: Encoder) -> None:
         with encoder.enter(Number.Sequence):
             encoder.write(self._oid, Number.ObjectIdentifier)
-            encoder.write(self.value)
+            # decoded varbinds keep their type, e.g. when echoed in an inform acknowledgment
+            if self.number == Number.ObjectIdentifier and isinstance(self.value, str):
+                encoder.write(self.value.lstrip("."), self.number)
+            else:
+                encoder.write(self.value, self.number)
 
 
 class PDU:
@@ -64,11 +239,15 @@ class PDU:
     _PDUType: PDUType
 
     def __init__(self, varbinds: List[SnmpVarbind]) -> None:
-        self.request_id = random.randrange(1, 2_147_483_647)
+        self.request_id = next(_request_ids)
         self.error_status: int = 0
         self.error_index: int = 0
         self.varbinds: List[SnmpVarbind] = varbinds
 
+    @property
+    def pdu_type(self) -> PDUType:
+        return self._PDUType
+
     def encode(self, encoder: Encoder) -> None:
         with encoder.enter(self._PDUType, Class.Context):
             encoder.write(self.request_id, Number.Integer)
@@ -86,11 +265,15 @@ class BulkPDU:
     _PDUType: PDUType
 
     def __init__(self, varbinds: List[SnmpVarbind], non_repeaters: int, max_repetitions: int) -> None:
-        self.request_id = random.randrange(1, 2_147_483_647)
+        self.request_id = next(_request_ids)
         self.non_repeaters: int = non_repeaters
         self.max_repetitions: int = max_repetitions
         self.varbinds: List[SnmpVarbind] = varbinds
 
+    @property
+    def pdu_type(self) -> PDUType:
+        return self._PDUType
+
     def encode(self, encoder: Encoder) -> None:
         with encoder.enter(self._PDUType, Class.Context):
             encoder.write(self.request_id, Number.Integer)
@@ -126,106 +309,665 @@ class SnmpV2Trap(PDU):
     _PDUType: PDUType = PDUType.SNMPv2Trap
 
 
+class InformRequest(PDU):
+    _PDUType: PDUType = PDUType.InformRequest
+
+
+class Report(PDU):
+    _PDUType: PDUType = PDUType.Report
+
+
 PDUs = Union[PDU, BulkPDU]
 
 
 class SnmpMessage:
     __slots__ = ("version", "community", "data")
 
-    def __init__(self, version: SnmpVersion, community: str, data: PDUs) -> None:
+    def __init__(self, version: SnmpVersion, community: Community, data: PDUs) -> None:
         self.version: SnmpVersion = version
-        self.community: str = community
+        self.community: Community = community
         self.data: PDUs = data
 
-    def encode(self) -> bytes:
-        encoder = Encoder()
+    @property
+    def pdu_type(self) -> PDUType:
+        return self.data.pdu_type
+
+    def encode(self, *, max_size: Optional[int] = None) -> bytes:
+        encoder = Encoder(max_size=max_size)
         with encoder.enter(Number.Sequence):
             encoder.write(self.version, Number.Integer)
             encoder.write(self.community, Number.OctetString)
             self.data.encode(encoder)
-        return encoder.output()
+        return _count_encoded(encoder.output(), self.pdu_type)
+
+
+def _encode_request(
+    community: Community,
+    request_id: int,
+    fields: Tuple[int, int],
+    oids: Iterable[str],
+    pdu_type: PDUType,
+    version: SnmpVersion,
+    max_size: Optional[int],
+) -> bytes:
+    # error-status and error-index, or non-repeaters and max-repetitions for GetBulk
+    encoder = Encoder(max_size=max_size)
+    with encoder.enter(Number.Sequence):
+        encoder.write(version, Number.Integer)
+        encoder.write(community, Number.OctetString)
+        with encoder.enter(pdu_type, Class.Context):
+            encoder.write(request_id, Number.Integer)
+            encoder.write(fields[0], Number.Integer)
+            encoder.write(fields[1], Number.Integer)
+            with encoder.enter(Number.Sequence):
+                for oid in oids:
+                    with encoder.enter(Number.Sequence):
+                        encoder.write(oid.lstrip("."), Number.ObjectIdentifier)
+                        encoder.write(None, Number.Null)
+    return _count_encoded(encoder.output(), pdu_type)
+
+
+def encode_get(
+    community: Community,
+    request_id: int,
+    oids: Iterable[str],
+    *,
+    pdu_type: PDUType = PDUType.GetRequest,
+    version: SnmpVersion = SnmpVersion.v2c,
+    max_size: Optional[int] = None,
+) -> bytes:
+    # builds the datagram directly, without PDU and varbind objects, for high rate polling
+    if pdu_type not in (PDUType.GetRequest, PDUType.GetNextRequest):
+        raise ValueError(f"Only GetRequest and GetNextRequest can be encoded, got {pdu_type!r}")
+    return _encode_request(community, request_id, (0, 0), oids, pdu_type, version, max_size)
+
+
+def encode_getbulk(
+    community: Community,
+    request_id: int,
+    non_repeaters: int,
+    max_repetitions: int,
+    oids: Iterable[str],
+    *,
+    version: SnmpVersion = SnmpVersion.v2c,
+    max_size: Optional[int] = None,
+) -> bytes:
+    if version == SnmpVersion.v1:
+        raise ValueError("GetBulkRequest is not supported by SNMPv1")
+    fields = (non_repeaters, max_repetitions)
+    return _encode_request(community, request_id, fields, oids, PDUType.GetBulkRequest, version, max_size)
+
+
+def encode_inform_response(message: "SnmpV2TrapMessage") -> bytes:
+    # the acknowledgment repeats the request-id and varbinds of the inform with error-status 0
+    if not isinstance(message.data, InformRequest):
+        raise ValueError(f"Expected an InformRequest, got {type(message.data).__name__}")
+    pdu = GetResponse(message.data.varbinds)
+    pdu.request_id = message.data.request_id
+    return SnmpMessage(message.version, message.community, pdu).encode()
+
+
+# called with each varbind as soon as it is decoded, e.g. a list's append or a queue's put_nowait
+VarbindCallback = Callable[[SnmpVarbind], Any]
+
+
+def _decode_varbind(decoder: Decoder) -> Optional[SnmpVarbind]:
+    # in partial mode varbinds without a usable header or oid are skipped, their errors are in decoder.errors
+    offset = decoder.tell()
+    try:
+        with decoder.enter():
+            _, oid = decoder.read()
+            tag, value = decoder.read()
+    except DecodeError as exc:
+        decoder.recover(exc, offset)
+        return None
+    if isinstance(oid, Malformed):
+        return None
+    return SnmpVarbind(oid, value, tag.nr | tag.cls)
+
+
+def _decode_varbinds(decoder: Decoder, on_varbind: Optional[VarbindCallback] = None) -> List[SnmpVarbind]:
+    # with a callback the varbinds are handed over one by one and the returned list stays empty
+    varbinds: List[SnmpVarbind] = []
+    emit = varbinds.append if on_varbind is None else on_varbind
+    with decoder.enter():
+        while not decoder.eof():
+            varbind = _decode_varbind(decoder)
+            if varbind is not None:
+                emit(varbind)
+    return varbinds
+
+
+def _decode_pdu(decoder: Decoder, pdu_cls: Type[PDU], on_varbind: Optional[VarbindCallback] = None) -> PDU:
+    with decoder.enter():
+        tag, value = decoder.read()
+        request_id = value
+
+        tag, value = decoder.read()
+        error_status = value
+
+        tag, value = decoder.read()
+        error_index = value
+
+        varbinds = _decode_varbinds(decoder, on_varbind)
+    pdu = pdu_cls(varbinds)
+    pdu.request_id = request_id
+    pdu.error_status = error_status
+    pdu.error_index = error_index
+    return pdu
 
 
 class SnmpResponse(SnmpMessage):
     @classmethod
     def decode(cls, data: bytes) -> "SnmpResponse":
+        return _counted(cls._decode, data)
+
+    @classmethod
+    def _decode(cls, data: bytes) -> "SnmpResponse":
         decoder = Decoder(data)
         with decoder.enter():
             tag, value = decoder.read()
             version = SnmpVersion(value)
 
             tag, value = decoder.read()
-            community = value.decode()
+            community = _decode_community(value)
 
-            with decoder.enter():
-                tag, value = decoder.read()
-                request_id = value
-
-                tag, value = decoder.read()
-                error_status = value
-
-                tag, value = decoder.read()
-                error_index = value
-
-                with decoder.enter():
-                    varbinds: List[SnmpVarbind] = []
-                    while not decoder.eof():
-                        with decoder.enter():
-                            _, value = decoder.read()
-                            oid = value
-                            _, value = decoder.read()
-                            varbinds.append(SnmpVarbind(oid, value))
-        response = GetResponse(varbinds)
-        response.request_id = request_id
-        response.error_status = error_status
-        response.error_index = error_index
+            tag = decoder.peek()
+            pdu_cls = Report if tag.nr == PDUType.Report else GetResponse
+
+            response = _decode_pdu(decoder, pdu_cls)
         return cls(version, community, response)
 
 
-class SnmpV2TrapMessage:
+class Response:
+    __slots__ = (
+        "version",
+        "community",
+        "pdu_type",
+        "request_id",
+        "error_status",
+        "error_index",
+        "varbinds",
+        "decode_errors",
+    )
+
+    def __init__(
+        self,
+        version: SnmpVersion,
+        community: Community,
+        pdu_type: PDUType,
+        request_id: int,
+        error_status: int,
+        error_index: int,
+        varbinds: List[SnmpVarbind],
+        decode_errors: Optional[List[DecodeError]] = None,
+    ) -> None:
+        self.version: SnmpVersion = version
+        self.community: Community = community
+        self.pdu_type: PDUType = pdu_type
+        self.request_id: int = request_id
+        self.error_status: int = error_status
+        self.error_index: int = error_index
+        self.varbinds: List[SnmpVarbind] = varbinds
+        self.decode_errors: List[DecodeError] = decode_errors or []
+
+
+def decode_response(data: bytes, *, partial: bool = False, view_threshold: Optional[int] = None) -> Response:
+    # with partial, malformed varbind values are replaced by asn1.Malformed and listed in decode_errors,
+    # varbinds with a malformed header or oid are left out and only listed in decode_errors,
+    # octet string and opaque values of at least view_threshold octets are returned as asn1.OctetView
+    return _counted(lambda data: _decode_response(data, partial, view_threshold), data)
+
+
+def _decode_response(data: bytes, partial: bool, view_threshold: Optional[int] = None) -> Response:
+    decoder = Decoder(data, partial=partial, view_threshold=view_threshold)
+    with decoder.enter():
+        version = decoder.read_integer()
+        community = decoder.read_octet_string()
+        tag = decoder.peek()
+        if tag.cls != Class.Context or tag.nr not in (PDUType.GetResponse, PDUType.Report):
+            raise Asn1SyntaxError(f"Expected a response PDU, got {tag!r}")
+        with decoder.enter():
+            request_id = decoder.read_integer()
+            error_status = decoder.read_integer()
+            error_index = decoder.read_integer()
+            if decoder.errors:
+                # a response without a valid header is not usable at all
+                raise decoder.errors[0]
+            varbinds = _decode_varbinds(decoder)
+    return Response(
+        SnmpVersion(version),
+        _decode_community(community),
+        PDUType(tag.nr),
+        request_id,
+        error_status,
+        error_index,
+        varbinds,
+        decoder.errors,
+    )
+
+
+_ENTERPRISE_SPECIFIC = 6
+_SYS_UP_TIME_OID = ".1.3.6.1.2.1.1.3.0"
+_SNMP_TRAP_OID_OID = ".1.3.6.1.6.3.1.1.4.1.0"
+_SNMP_TRAP_ENTERPRISE_OID = ".1.3.6.1.6.3.1.1.4.3.0"
+_SNMP_TRAP_ADDRESS_OID = ".1.3.6.1.6.3.18.1.3.0"
+_SNMP_TRAPS_OID = ".1.3.6.1.6.3.1.1.5"
+
+
+class SnmpV1TrapPDU:
+    __slots__ = ("enterprise", "agent_addr", "generic_trap", "specific_trap", "time_stamp", "varbinds")
+
+    def __init__(
+        self,
+        enterprise: str,
+        agent_addr: ipaddress.IPv4Address,
+        generic_trap: int,
+        specific_trap: int,
+        time_stamp: int,
+        varbinds: List[SnmpVarbind],
+    ) -> None:
+        self.enterprise: str = enterprise
+        self.agent_addr: ipaddress.IPv4Address = agent_addr
+        self.generic_trap: int = generic_trap
+        self.specific_trap: int = specific_trap
+        self.time_stamp: int = time_stamp
+        self.varbinds: List[SnmpVarbind] = varbinds
+
+    @property
+    def pdu_type(self) -> PDUType:
+        return PDUType.SNMPv1Trap
+
+    @property
+    def trap_oid(self) -> str:
+        # snmpTrapOID.0 of the trap as translated by RFC 3584
+        if self.generic_trap == _ENTERPRISE_SPECIFIC:
+            return f".{self.enterprise.strip('.')}.0.{self.specific_trap}"
+        return f"{_SNMP_TRAPS_OID}.{self.generic_trap + 1}"
+
+    def to_v2_varbinds(self) -> List[SnmpVarbind]:
+        # sysUpTime.0 and snmpTrapOID.0 go first, the agent address and enterprise are appended
+        varbinds = [
+            SnmpVarbind(_SYS_UP_TIME_OID, self.time_stamp, Number.TimeTicks),
+            SnmpVarbind(_SNMP_TRAP_OID_OID, self.trap_oid, Number.ObjectIdentifier),
+        ]
+        varbinds.extend(self.varbinds)
+        oids = {varbind.oid for varbind in self.varbinds}
+        if _SNMP_TRAP_ADDRESS_OID not in oids:
+            varbinds.append(SnmpVarbind(_SNMP_TRAP_ADDRESS_OID, self.agent_addr, Number.IPAddress))
+        if _SNMP_TRAP_ENTERPRISE_OID not in oids:
+            enterprise = f".{self.enterprise.strip('.')}"
+            varbinds.append(SnmpVarbind(_SNMP_TRAP_ENTERPRISE_OID, enterprise, Number.ObjectIdentifier))
+        return varbinds
+
+    @classmethod
+    def from_v2_varbinds(cls, varbinds: List[SnmpVarbind]) -> "SnmpV1TrapPDU":
+        # RFC 3584 3.2, snmpTrapAddress.0 and snmpTrapEnterprise.0 go back into the header fields
+        if len(varbinds) < 2 or varbinds[0].oid != _SYS_UP_TIME_OID or varbinds[1].oid != _SNMP_TRAP_OID_OID:
+            raise ValueError("Notification should start with sysUpTime.0 and snmpTrapOID.0")
+        if any(varbind.number == Number.Counter64 for varbind in varbinds):
+            raise ValueError("Notifications with Counter64 values cannot be translated to SNMPv1")
+        time_stamp = varbinds[0].value
+        trap_oid = varbinds[1].value
+        if not isinstance(time_stamp, int) or not isinstance(trap_oid, str):
+            raise ValueError("Invalid sysUpTime.0 or snmpTrapOID.0 value")
+        values = {varbind.oid: varbind.value for varbind in varbinds[2:]}
+
+        prefix, _, last = f".{trap_oid.strip('.')}".rpartition(".")
+        if prefix == _SNMP_TRAPS_OID and 1 <= int(last) <= _ENTERPRISE_SPECIFIC:
+            enterprise = str(values.get(_SNMP_TRAP_ENTERPRISE_OID, _SNMP_TRAPS_OID))
+            generic_trap = int(last) - 1
+            specific_trap = 0
+        else:
+            # <enterprise>.0.<specific> or <enterprise>.<specific>
+            enterprise = prefix[:-2] if prefix.endswith(".0") else prefix
+            generic_trap = _ENTERPRISE_SPECIFIC
+            specific_trap = int(last)
+        agent_addr = values.get(_SNMP_TRAP_ADDRESS_OID)
+        if not isinstance(agent_addr, ipaddress.IPv4Address):
+            agent_addr = ipaddress.IPv4Address(0)
+
+        return cls(
+            f".{enterprise.strip('.')}",
+            agent_addr,
+            generic_trap,
+            specific_trap,
+            time_stamp,
+            [vb for vb in varbinds[2:] if vb.oid not in (_SNMP_TRAP_ADDRESS_OID, _SNMP_TRAP_ENTERPRISE_OID)],
+        )
+
+    def encode(self, encoder: Encoder) -> None:
+        with encoder.enter(PDUType.SNMPv1Trap, Class.Context):
+            encoder.write(self.enterprise.strip("."), Number.ObjectIdentifier)
+            encoder.write(self.agent_addr, Number.IPAddress)
+            encoder.write(self.generic_trap, Number.Integer)
+            encoder.write(self.specific_trap, Number.Integer)
+            encoder.write(self.time_stamp, Number.TimeTicks)
+
+            with encoder.enter(Number.Sequence):
+                for varbind in self.varbinds:
+                    varbind.encode(encoder)
+
+
+class SnmpV1TrapMessage:
     __slots__ = ("version", "community", "data")
 
-    def __init__(self, version: SnmpVersion, community: str, data: PDU) -> None:
+    def __init__(self, version: SnmpVersion, community: Community, data: SnmpV1TrapPDU) -> None:
         self.version: SnmpVersion = version
-        self.community: str = community
-        self.data: PDU = data
+        self.community: Community = community
+        self.data: SnmpV1TrapPDU = data
+
+    @property
+    def pdu_type(self) -> PDUType:
+        return self.data.pdu_type
+
+    def encode(self) -> bytes:
+        encoder = Encoder()
+        with encoder.enter(Number.Sequence):
+            encoder.write(self.version, Number.Integer)
+            encoder.write(self.community, Number.OctetString)
+            self.data.encode(encoder)
+        return _count_encoded(encoder.output(), self.pdu_type)
+
+    def to_v2(self) -> "SnmpV2TrapMessage":
+        pdu = SnmpV2Trap(self.data.to_v2_varbinds())
+        pdu.request_id = 0
+        return SnmpV2TrapMessage(SnmpVersion.v2c, self.community, pdu)
 
     @classmethod
-    def decode(cls, data: bytes) -> Optional["SnmpV2TrapMessage"]:
-        decoder = Decoder(data)
-        with decoder.enter():
-            tag, value = decoder.read()
-            version = SnmpVersion(value)
-            if version != SnmpVersion.v2c:
-                return None
+    def decode(cls, data: bytes) -> Optional["SnmpV1TrapMessage"]:
+        message = parse_trap(data)
+        if not isinstance(message, cls):
+            return None
+        return message
 
-            tag, value = decoder.read()
-            community = value.decode()
 
-            tag = decoder.peek()
-            if tag.cls != Class.Context or tag.nr != PDUType.SNMPv2Trap:
-                return None
+class SnmpV2TrapMessage:
+    __slots__ = ("version", "community", "data")
+
+    def __init__(self, version: SnmpVersion, community: Community, data: PDU) -> None:
+        self.version: SnmpVersion = version
+        self.community: Community = community
+        self.data: PDU = data
+
+    @property
+    def pdu_type(self) -> PDUType:
+        return self.data.pdu_type
 
+    def to_v1(self) -> SnmpV1TrapMessage:
+        return SnmpV1TrapMessage(SnmpVersion.v1, self.community, SnmpV1TrapPDU.from_v2_varbinds(self.data.varbinds))
+
+    @classmethod
+    def decode(cls, data: bytes) -> Optional["SnmpV2TrapMessage"]:
+        message = parse_trap(data)
+        if not isinstance(message, cls) or not isinstance(message.data, SnmpV2Trap):
+            return None
+        return message
+
+
+def _decode_v1_trap_pdu(decoder: Decoder, on_varbind: Optional[VarbindCallback] = None) -> SnmpV1TrapPDU:
+    with decoder.enter():
+        _, enterprise = decoder.read()
+        _, agent_addr = decoder.read()
+        _, generic_trap = decoder.read()
+        _, specific_trap = decoder.read()
+        _, time_stamp = decoder.read()
+        varbinds = _decode_varbinds(decoder, on_varbind)
+    return SnmpV1TrapPDU(enterprise, agent_addr, generic_trap, specific_trap, time_stamp, varbinds)
+
+
+def parse_trap(
+    data: bytes, *, on_varbind: Optional[VarbindCallback] = None
+) -> Union[SnmpV1TrapMessage, SnmpV2TrapMessage, None]:
+    # with on_varbind the varbinds are passed to it while decoding and the message's varbinds are empty,
+    # varbinds handed over before a decode error are not taken back
+    return _counted(functools.partial(_parse_trap, on_varbind=on_varbind), data)
+
+
+def _parse_trap(
+    data: bytes, on_varbind: Optional[VarbindCallback] = None
+) -> Union[SnmpV1TrapMessage, SnmpV2TrapMessage, None]:
+    decoder = Decoder(data)
+    with decoder.enter():
+        tag, value = decoder.read()
+        version = SnmpVersion(value)
+
+        tag, value = decoder.read()
+        community = _decode_community(value)
+
+        tag = decoder.peek()
+        if tag.cls != Class.Context:
+            return None
+
+        if version == SnmpVersion.v1 and tag.nr == PDUType.SNMPv1Trap:
+            return SnmpV1TrapMessage(version, community, _decode_v1_trap_pdu(decoder, on_varbind))
+
+        if version == SnmpVersion.v2c and tag.nr in (PDUType.SNMPv2Trap, PDUType.InformRequest):
+            pdu_cls = SnmpV2Trap if tag.nr == PDUType.SNMPv2Trap else InformRequest
+            return SnmpV2TrapMessage(version, community, _decode_pdu(decoder, pdu_cls, on_varbind))
+    return None
+
+
+_PDU_CLASSES: Dict[int, Type[PDU]] = {
+    PDUType.GetRequest: GetRequest,
+    PDUType.GetNextRequest: GetNextRequest,
+    PDUType.GetResponse: GetResponse,
+    PDUType.SetRequest: SetRequest,
+    PDUType.Report: Report,
+}
+
+
+def _decode_bulk_pdu(decoder: Decoder, on_varbind: Optional[VarbindCallback] = None) -> GetBulkRequest:
+    with decoder.enter():
+        request_id = decoder.read_integer()
+        non_repeaters = decoder.read_integer()
+        max_repetitions = decoder.read_integer()
+        varbinds = _decode_varbinds(decoder, on_varbind)
+    pdu = GetBulkRequest(varbinds, non_repeaters, max_repetitions)
+    pdu.request_id = request_id
+    return pdu
+
+
+def parse_message(
+    data: bytes, *, on_varbind: Optional[VarbindCallback] = None
+) -> Union[SnmpMessage, SnmpV1TrapMessage, SnmpV2TrapMessage]:
+    # decodes a SNMPv1/v2c message of any PDU type, for captures where both directions are seen,
+    # on_varbind works as for parse_trap
+    return _counted(functools.partial(_parse_message, on_varbind=on_varbind), data)
+
+
+def _parse_message(
+    data: bytes, on_varbind: Optional[VarbindCallback] = None
+) -> Union[SnmpMessage, SnmpV1TrapMessage, SnmpV2TrapMessage]:
+    decoder = Decoder(data)
+    with decoder.enter():
+        version = SnmpVersion(decoder.read_integer())
+        if version == SnmpVersion.v3:
+            raise Asn1SyntaxError("SNMPv3 messages are not supported.")
+        community = _decode_community(decoder.read_octet_string())
+
+        tag = decoder.peek()
+        if tag.cls != Class.Context:
+            raise Asn1SyntaxError(f"Expected a PDU, got {tag!r}")
+        if tag.nr == PDUType.SNMPv1Trap:
+            return SnmpV1TrapMessage(version, community, _decode_v1_trap_pdu(decoder, on_varbind))
+        if tag.nr in (PDUType.SNMPv2Trap, PDUType.InformRequest):
+            pdu_cls = SnmpV2Trap if tag.nr == PDUType.SNMPv2Trap else InformRequest
+            return SnmpV2TrapMessage(version, community, _decode_pdu(decoder, pdu_cls, on_varbind))
+        if tag.nr == PDUType.GetBulkRequest:
+            return SnmpMessage(version, community, _decode_bulk_pdu(decoder, on_varbind))
+        if tag.nr not in _PDU_CLASSES:
+            raise Asn1SyntaxError(f"Unknown PDU type {tag.nr}")
+        return SnmpMessage(version, community, _decode_pdu(decoder, _PDU_CLASSES[tag.nr], on_varbind))
+
+
+def peek_request_id(data: bytes) -> int:
+    # reads only up to the request-id (msgID for SNMPv3), the rest of the message is not decoded
+    decoder = Decoder(data)
+    with decoder.enter():
+        version = decoder.read_integer()
+        if version == SnmpVersion.v3:
             with decoder.enter():
-                tag, value = decoder.read()
-                request_id = value
-
-                tag, value = decoder.read()
-                error_status = value
-
-                tag, value = decoder.read()
-                error_index = value
-
-                with decoder.enter():
-                    varbinds: List[SnmpVarbind] = []
-                    while not decoder.eof():
-                        with decoder.enter():
-                            _, value = decoder.read()
-                            oid = value
-                            _, value = decoder.read()
-                            varbinds.append(SnmpVarbind(oid, value))
-        response = SnmpV2Trap(varbinds)
-        response.request_id = request_id
-        response.error_status = error_status
-        response.error_index = error_index
-        return cls(version, community, response)
+                return decoder.read_integer()
+        decoder.skip()
+        with decoder.enter():
+            return decoder.read_integer()
+
+
+def peek_pdu_type(data: bytes) -> PDUType:
+    # classifies a SNMPv1/v2c message by its PDU tag, the PDU itself is not decoded
+    decoder = Decoder(data)
+    with decoder.enter():
+        version = decoder.read_integer()
+        if version == SnmpVersion.v3:
+            raise Asn1SyntaxError("SNMPv3 messages are not supported.")
+        decoder.skip()
+        tag = decoder.peek()
+    if tag.cls != Class.Context or tag.nr not in PDUType.__members__.values():
+        raise Asn1SyntaxError(f"Unknown PDU type {tag!r}")
+    return PDUType(tag.nr)
+
+
+def error_status_exception(
+    error_status: int, error_index: int, varbinds: List[SnmpVarbind]
+) -> Optional[SnmpErrorStatus]:
+    if error_status == ErrorStatus.NoError:
+        return None
+    oid = None
+    if len(varbinds) > 0 and 0 <= error_index - 1 < len(varbinds):
+        oid = varbinds[error_index - 1].oid
+    # unknown codes from misbehaving agents still fail the request
+    exception_cls = _ERROR_STATUS_TO_EXCEPTION.get(error_status, SnmpErrorStatus)
+    return exception_cls(error_index, oid)
+
+
+def decode_many(datagrams: Iterable[bytes], decode: Callable[[bytes], Any] = parse_trap) -> List[Any]:
+    results: List[Any] = []
+    for data in datagrams:
+        try:
+            results.append(decode(data))
+        except Exception as exc:
+            results.append(exc)
+    return results
+
+
+def decode_many_parallel(
+    datagrams: List[bytes],
+    *,
+    workers: Optional[int] = None,
+    decode: Callable[[bytes], Any] = parse_trap,
+) -> List[Any]:
+    # threads would serialize on the GIL, so the batch is split between worker processes
+    workers = workers or os.cpu_count() or 1
+    chunk_size = -(-len(datagrams) // workers)
+    if workers == 1 or chunk_size <= 1:
+        return decode_many(datagrams, decode)
+
+    chunks = [datagrams[i : i + chunk_size] for i in range(0, len(datagrams), chunk_size)]
+    results: List[Any] = []
+    with ProcessPoolExecutor(max_workers=len(chunks)) as executor:
+        for chunk_results in executor.map(decode_many, chunks, [decode] * len(chunks)):
+            results.extend(chunk_results)
+    return results
+
+
+def _value_to_json(value: Any) -> Any:
+    if isinstance(value, bytes):
+        return value.hex()
+    if isinstance(value, ipaddress.IPv4Address):
+        return str(value)
+    return value
+
+
+def _varbind_to_dict(varbind: SnmpVarbind) -> Dict[str, Any]:
+    number = varbind.number
+    if number is None:
+        type_name = None
+    elif number in Number.__members__.values():
+        type_name = Number(number).name
+    else:
+        type_name = hex(number)
+    return {"oid": varbind.oid, "type": type_name, "value": _value_to_json(varbind.value)}
+
+
+def to_dict(message: Union[SnmpMessage, SnmpV1TrapMessage, SnmpV2TrapMessage]) -> Dict[str, Any]:
+    pdu: Dict[str, Any]
+    data = message.data
+    if isinstance(data, SnmpV1TrapPDU):
+        pdu = {
+            "type": PDUType.SNMPv1Trap.name,
+            "enterprise": data.enterprise,
+            "agent_addr": str(data.agent_addr),
+            "generic_trap": data.generic_trap,
+            "specific_trap": data.specific_trap,
+            "time_stamp": data.time_stamp,
+        }
+    elif isinstance(data, BulkPDU):
+        pdu = {
+            "type": data._PDUType.name,
+            "request_id": data.request_id,
+            "non_repeaters": data.non_repeaters,
+            "max_repetitions": data.max_repetitions,
+        }
+    else:
+        pdu = {
+            "type": data._PDUType.name,
+            "request_id": data.request_id,
+            "error_status": data.error_status,
+            "error_index": data.error_index,
+        }
+    pdu["varbinds"] = [_varbind_to_dict(varbind) for varbind in data.varbinds]
+    return {"version": message.version.name, "community": _value_to_json(message.community), "pdu": pdu}
+
+
+def first_outside(base_oid: str, oids: Iterable[str]) -> int:
+    # index of the first oid that is not under base_oid, e.g. where a walk has left the subtree, or -1
+    prefix = f".{base_oid.strip('.')}."
+    for i, oid in enumerate(oids):
+        if not (oid if oid.startswith(".") else f".{oid}").startswith(prefix):
+            return i
+    return -1
+
+
+class MismatchKind(enum.Enum):
+    Missing = "missing"
+    Unexpected = "unexpected"
+    WrongOid = "wrong oid"
+    NotIncreasing = "not increasing"
+
+
+class VarbindMismatch(NamedTuple):
+    index: int
+    kind: MismatchKind
+    # None for unexpected and missing varbinds respectively
+    requested: Optional[str]
+    received: Optional[str]
+
+
+def _oid_tuple(oid: str) -> Tuple[int, ...]:
+    return tuple(int(subid) for subid in oid.strip(".").split(".") if subid)
+
+
+def correlate_varbinds(
+    oids: Sequence[str], varbinds: Sequence[SnmpVarbind], pdu_type: PDUType = PDUType.GetRequest
+) -> List[VarbindMismatch]:
+    # get and set responses repeat the requested oids, get-next responses return their successors
+    if pdu_type not in (PDUType.GetRequest, PDUType.SetRequest, PDUType.GetNextRequest):
+        raise ValueError(f"Cannot correlate varbinds of {PDUType(pdu_type).name} requests")
+    mismatches: List[VarbindMismatch] = []
+    for index, oid in enumerate(oids):
+        requested = f".{oid.strip('.')}"
+        if index >= len(varbinds):
+            mismatches.append(VarbindMismatch(index, MismatchKind.Missing, requested, None))
+            continue
+        received = varbinds[index].oid
+        if pdu_type != PDUType.GetNextRequest:
+            if received != requested:
+                mismatches.append(VarbindMismatch(index, MismatchKind.WrongOid, requested, received))
+        elif _oid_tuple(received) <= _oid_tuple(requested):
+            # at the end of the mib view the requested oid comes back with endOfMibView
+            if not (received == requested and varbinds[index].number == Number.EndOfMibView):
+                mismatches.append(VarbindMismatch(index, MismatchKind.NotIncreasing, requested, received))
+    for index in range(len(oids), len(varbinds)):
+        mismatches.append(VarbindMismatch(index, MismatchKind.Unexpected, None, varbinds[index].oid))
+    return mismatches
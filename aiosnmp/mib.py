@@ -0,0 +1,523 @@
+__all__ = ("MibObject", "MibTextualConvention", "MibModule", "Mib", "OidResolver", "parse_mib")
+
+import re
+from typing import Dict, Iterator, List, Mapping, Optional, Set, Tuple, Union
+
+from .exceptions import SnmpMibError
+
+_TOKEN_RE = re.compile(
+    r"""
+    (?P<space>\s+)
+    |(?P<comment>--.*?(?:--|$))
+    |(?P<string>"[^"]*")
+    |(?P<quoted>'[0-9A-Fa-f]*'[HhBb])
+    |(?P<assign>::=)
+    |(?P<range>\.\.)
+    |(?P<word>[A-Za-z][A-Za-z0-9_-]*)
+    |(?P<number>-?[0-9]+)
+    |(?P<punct>[{}()\[\],;|.<>])
+    """,
+    re.VERBOSE | re.MULTILINE,
+)
+
+_CLAUSES = {
+    "SYNTAX",
+    "UNITS",
+    "MAX-ACCESS",
+    "ACCESS",
+    "MIN-ACCESS",
+    "STATUS",
+    "DESCRIPTION",
+    "REFERENCE",
+    "INDEX",
+    "AUGMENTS",
+    "DEFVAL",
+    "DISPLAY-HINT",
+    "OBJECTS",
+    "NOTIFICATIONS",
+    "LAST-UPDATED",
+    "ORGANIZATION",
+    "CONTACT-INFO",
+    "REVISION",
+    "ENTERPRISE",
+    "VARIABLES",
+    "MODULE",
+    "MANDATORY-GROUPS",
+    "GROUP",
+    "OBJECT",
+    "WRITE-SYNTAX",
+    "PRODUCT-RELEASE",
+    "SUPPORTS",
+    "INCLUDES",
+    "VARIATION",
+    "CREATION-REQUIRES",
+}
+
+# nodes defined by SNMPv2-SMI and RFC1155-SMI, so they resolve without loading those modules
+_ROOTS = {
+    "ccitt": ".0",
+    "zeroDotZero": ".0.0",
+    "iso": ".1",
+    "org": ".1.3",
+    "dod": ".1.3.6",
+    "internet": ".1.3.6.1",
+    "directory": ".1.3.6.1.1",
+    "mgmt": ".1.3.6.1.2",
+    "mib-2": ".1.3.6.1.2.1",
+    "transmission": ".1.3.6.1.2.1.10",
+    "experimental": ".1.3.6.1.3",
+    "private": ".1.3.6.1.4",
+    "enterprises": ".1.3.6.1.4.1",
+    "security": ".1.3.6.1.5",
+    "snmpV2": ".1.3.6.1.6",
+    "snmpDomains": ".1.3.6.1.6.1",
+    "snmpProxys": ".1.3.6.1.6.2",
+    "snmpModules": ".1.3.6.1.6.3",
+    "joint-iso-ccitt": ".2",
+}
+
+OidPart = Union[str, int]
+
+
+class MibObject:
+    __slots__ = (
+        "name",
+        "module",
+        "macro",
+        "oid_parts",
+        "syntax",
+        "named_values",
+        "access",
+        "status",
+        "units",
+        "index",
+        "description",
+        "oid",
+    )
+
+    def __init__(self, name: str, module: str, macro: str, oid_parts: List[OidPart]) -> None:
+        self.name: str = name
+        self.module: str = module
+        self.macro: str = macro
+        self.oid_parts: List[OidPart] = oid_parts
+        self.syntax: Optional[str] = None
+        # named numbers of INTEGER enumerations and BITS
+        self.named_values: Dict[int, str] = {}
+        self.access: Optional[str] = None
+        self.status: Optional[str] = None
+        self.units: Optional[str] = None
+        self.index: List[str] = []
+        self.description: Optional[str] = None
+        # resolved by Mib, None while the parent is unknown
+        self.oid: Optional[str] = None
+
+    def __repr__(self) -> str:
+        return f"<MibObject {self.module}::{self.name} {self.macro} oid={self.oid}>"
+
+
+class MibTextualConvention:
+    __slots__ = ("name", "module", "syntax", "named_values", "display_hint", "status", "description")
+
+    def __init__(self, name: str, module: str, syntax: str, named_values: Dict[int, str]) -> None:
+        self.name: str = name
+        self.module: str = module
+        self.syntax: str = syntax
+        self.named_values: Dict[int, str] = named_values
+        self.display_hint: Optional[str] = None
+        self.status: Optional[str] = None
+        self.description: Optional[str] = None
+
+    def __repr__(self) -> str:
+        return f"<MibTextualConvention {self.module}::{self.name} syntax={self.syntax}>"
+
+
+class MibModule:
+    __slots__ = ("name", "imports", "objects", "textual_conventions")
+
+    def __init__(self, name: str) -> None:
+        self.name: str = name
+        # imported symbol -> module it is imported from
+        self.imports: Dict[str, str] = {}
+        self.objects: Dict[str, MibObject] = {}
+        self.textual_conventions: Dict[str, MibTextualConvention] = {}
+
+    def __repr__(self) -> str:
+        return f"<MibModule {self.name} objects={len(self.objects)}>"
+
+
+def _tokenize(text: str) -> List[Tuple[str, int]]:
+    tokens: List[Tuple[str, int]] = []
+    line = 1
+    pos = 0
+    while pos < len(text):
+        match = _TOKEN_RE.match(text, pos)
+        if match is None:
+            raise SnmpMibError(f"Unexpected character {text[pos]!r} at line {line}")
+        kind = match.lastgroup
+        value = match.group()
+        if kind not in ("space", "comment"):
+            tokens.append((value, line))
+        line += value.count("\n")
+        pos = match.end()
+    return tokens
+
+
+def _parse_syntax(tokens: List[str]) -> Tuple[str, Dict[int, str]]:
+    i = 0
+    if tokens[i : i + 1] == ["["]:
+        i = tokens.index("]") + 1
+        if tokens[i : i + 1] in (["IMPLICIT"], ["EXPLICIT"]):
+            i += 1
+    if not tokens[i:]:
+        raise SnmpMibError("Empty SYNTAX")
+
+    if tokens[i] in ("OCTET", "OBJECT") and tokens[i + 1 : i + 2] in (["STRING"], ["IDENTIFIER"]):
+        syntax = f"{tokens[i]} {tokens[i + 1]}"
+        i += 2
+    elif tokens[i] in ("SEQUENCE", "SET") and tokens[i + 1 : i + 2] == ["OF"] and len(tokens) > i + 2:
+        syntax = f"{tokens[i]} OF {tokens[i + 2]}"
+        i += 3
+    else:
+        syntax = tokens[i]
+        i += 1
+
+    named_values: Dict[int, str] = {}
+    if tokens[i : i + 1] == ["{"] and syntax not in ("SEQUENCE", "SET", "CHOICE"):
+        i += 1
+        while i + 3 < len(tokens) and tokens[i] != "}":
+            if tokens[i + 1] == "(" and tokens[i + 3] == ")":
+                try:
+                    named_values[int(tokens[i + 2])] = tokens[i]
+                except ValueError:
+                    raise SnmpMibError(f"Invalid named number {tokens[i]}({tokens[i + 2]})")
+                i += 4
+            else:
+                i += 1
+    return syntax, named_values
+
+
+class _Parser:
+    __slots__ = ("tokens", "pos")
+
+    def __init__(self, text: str) -> None:
+        self.tokens: List[Tuple[str, int]] = _tokenize(text)
+        self.pos: int = 0
+
+    def at_end(self) -> bool:
+        return self.pos >= len(self.tokens)
+
+    def peek(self, offset: int = 0) -> Optional[str]:
+        if self.pos + offset >= len(self.tokens):
+            return None
+        return self.tokens[self.pos + offset][0]
+
+    def next(self) -> str:
+        if self.at_end():
+            raise SnmpMibError("Unexpected end of MIB")
+        token = self.tokens[self.pos][0]
+        self.pos += 1
+        return token
+
+    def expect(self, value: str) -> None:
+        line = self.tokens[self.pos][1] if not self.at_end() else self.tokens[-1][1]
+        token = self.next()
+        if token != value:
+            raise SnmpMibError(f"Expected {value!r}, got {token!r} at line {line}")
+
+    def balanced(self, opening: str, closing: str) -> List[str]:
+        self.expect(opening)
+        tokens = [opening]
+        depth = 1
+        while depth:
+            token = self.next()
+            if token == opening:
+                depth += 1
+            elif token == closing:
+                depth -= 1
+            tokens.append(token)
+        return tokens
+
+    def skip_type(self) -> List[str]:
+        tokens: List[str] = []
+        if self.peek() == "[":
+            tokens += self.balanced("[", "]")
+            if self.peek() in ("IMPLICIT", "EXPLICIT"):
+                tokens.append(self.next())
+        token = self.next()
+        tokens.append(token)
+        if token in ("SEQUENCE", "SET") and self.peek() == "OF":
+            tokens.append(self.next())
+            tokens += self.skip_type()
+            return tokens
+        if token in ("OCTET", "OBJECT"):
+            tokens.append(self.next())
+        if self.peek() == "{":
+            tokens += self.balanced("{", "}")
+        if self.peek() == "(":
+            tokens += self.balanced("(", ")")
+        return tokens
+
+    def oid_value(self) -> List[OidPart]:
+        parts: List[OidPart] = []
+        tokens = self.balanced("{", "}")[1:-1]
+        index = 0
+        while index < len(tokens):
+            token = tokens[index]
+            if token == "(":
+                # a ``name(number)`` component is identified by its number, at any position
+                if not parts or index + 2 >= len(tokens) or tokens[index + 2] != ")":
+                    raise SnmpMibError(f"Invalid OID component {' '.join(tokens[index - 1 : index + 3])}")
+                parts[-1] = self._number(tokens[index + 1])
+                index += 3
+                continue
+            parts.append(int(token) if token.lstrip("-").isdigit() else token)
+            index += 1
+        return parts
+
+    @staticmethod
+    def _number(token: str) -> int:
+        if not token.isdigit():
+            raise SnmpMibError(f"Expected a number, got {token}")
+        return int(token)
+
+    def parse(self) -> List[MibModule]:
+        modules: List[MibModule] = []
+        while not self.at_end():
+            modules.append(self.module())
+        return modules
+
+    def module(self) -> MibModule:
+        module = MibModule(self.next())
+        if self.peek() == "{":
+            self.balanced("{", "}")
+        self.expect("DEFINITIONS")
+        # tagging defaults like ``IMPLICIT TAGS`` do not matter for SMI
+        while self.peek() not in ("::=", None):
+            self.next()
+        self.expect("::=")
+        self.expect("BEGIN")
+        while True:
+            token = self.peek()
+            if token is None:
+                raise SnmpMibError(f"Missing END of module {module.name}")
+            if token == "END":
+                self.next()
+                return module
+            if token == "IMPORTS":
+                self.next()
+                self.imports(module)
+            elif token == "EXPORTS":
+                while self.next() != ";":
+                    pass
+            else:
+                self.assignment(module)
+
+    def imports(self, module: MibModule) -> None:
+        symbols: List[str] = []
+        while True:
+            token = self.next()
+            if token == ";":
+                return
+            if token == "FROM":
+                source = self.next()
+                for symbol in symbols:
+                    module.imports[symbol] = source
+                symbols = []
+            elif token != ",":
+                symbols.append(token)
+
+    def assignment(self, module: MibModule) -> None:
+        name = self.next()
+        token = self.peek()
+        if token == "MACRO":
+            while self.next() != "END":
+                pass
+        elif token == "::=":
+            self.next()
+            if self.peek() == "TEXTUAL-CONVENTION":
+                self.next()
+                self.textual_convention(module, name)
+            else:
+                self.skip_type()
+        elif token == "OBJECT" and self.peek(1) == "IDENTIFIER" and self.peek(2) == "::=":
+            self.pos += 3
+            module.objects[name] = MibObject(name, module.name, "OBJECT IDENTIFIER", self.oid_value())
+        else:
+            self.macro_value(module, name)
+
+    def textual_convention(self, module: MibModule, name: str) -> None:
+        clauses: Dict[str, str] = {}
+        while True:
+            keyword = self.next()
+            if keyword == "SYNTAX":
+                syntax, named_values = _parse_syntax(self.skip_type())
+                break
+            clauses[keyword] = self.next().strip('"')
+        convention = MibTextualConvention(name, module.name, syntax, named_values)
+        convention.display_hint = clauses.get("DISPLAY-HINT")
+        convention.status = clauses.get("STATUS")
+        convention.description = clauses.get("DESCRIPTION")
+        module.textual_conventions[name] = convention
+
+    def macro_value(self, module: MibModule, name: str) -> None:
+        macro = self.next()
+        clauses: Dict[str, List[str]] = {}
+        clause: Optional[List[str]] = None
+        depth = 0
+        while True:
+            token = self.next()
+            if token == "::=" and depth == 0:
+                break
+            if token in ("{", "("):
+                depth += 1
+            elif token in ("}", ")"):
+                depth -= 1
+            if depth == 0 and token in _CLAUSES and not (token == "OBJECT" and self.peek() == "IDENTIFIER"):
+                clause = clauses.setdefault(token, []) if token not in clauses else []
+                continue
+            if clause is not None:
+                clause.append(token)
+
+        if self.peek() == "{":
+            oid_parts = self.oid_value()
+        else:
+            value = self.next()
+            if macro != "TRAP-TYPE" or not value.isdigit() or "ENTERPRISE" not in clauses:
+                return
+            # SMIv1 traps map to ``enterprise.0.specific-trap`` (RFC 3584)
+            oid_parts = [clauses["ENTERPRISE"][0], 0, int(value)]
+
+        obj = MibObject(name, module.name, macro, oid_parts)
+        if "SYNTAX" in clauses:
+            obj.syntax, obj.named_values = _parse_syntax(clauses["SYNTAX"])
+        for keyword in ("MAX-ACCESS", "ACCESS"):
+            if keyword in clauses:
+                obj.access = clauses[keyword][0]
+                break
+        if "STATUS" in clauses:
+            obj.status = clauses["STATUS"][0]
+        if "UNITS" in clauses:
+            obj.units = clauses["UNITS"][0].strip('"')
+        if "DESCRIPTION" in clauses:
+            obj.description = clauses["DESCRIPTION"][0].strip('"')
+        if "INDEX" in clauses:
+            obj.index = [token for token in clauses["INDEX"] if token not in ("{", "}", ",", "IMPLIED")]
+        elif "AUGMENTS" in clauses:
+            obj.index = [token for token in clauses["AUGMENTS"] if token not in ("{", "}")]
+        module.objects[name] = obj
+
+
+def parse_mib(text: str) -> List[MibModule]:
+    return _Parser(text).parse()
+
+
+class Mib:
+    __slots__ = ("modules", "_objects", "_textual_conventions")
+
+    def __init__(self) -> None:
+        self.modules: Dict[str, MibModule] = {}
+        self._objects: Dict[str, MibObject] = {}
+        self._textual_conventions: Dict[str, MibTextualConvention] = {}
+
+    def __contains__(self, name: str) -> bool:
+        return name in self._objects
+
+    def __getitem__(self, name: str) -> MibObject:
+        return self._objects[name]
+
+    def __iter__(self) -> Iterator[MibObject]:
+        return iter(self._objects.values())
+
+    def __len__(self) -> int:
+        return len(self._objects)
+
+    def get(self, name: str) -> Optional[MibObject]:
+        return self._objects.get(name)
+
+    def textual_convention(self, name: str) -> Optional[MibTextualConvention]:
+        return self._textual_conventions.get(name)
+
+    def oid(self, name: str) -> str:
+        if name in _ROOTS:
+            return _ROOTS[name]
+        obj = self._objects.get(name)
+        if obj is None:
+            raise SnmpMibError(f"Unknown MIB object {name}")
+        if obj.oid is None:
+            raise SnmpMibError(f"Cannot resolve oid of {obj.module}::{name}")
+        return obj.oid
+
+    def load(self, text: str) -> List[MibModule]:
+        modules = parse_mib(text)
+        for module in modules:
+            self.modules[module.name] = module
+            self._objects.update(module.objects)
+            self._textual_conventions.update(module.textual_conventions)
+        self._resolve()
+        return modules
+
+    def load_file(self, path: str) -> List[MibModule]:
+        with open(path, encoding="utf-8", errors="replace") as f:
+            return self.load(f.read())
+
+    def _resolve(self) -> None:
+        for obj in self._objects.values():
+            self._resolve_object(obj, set())
+
+    def _resolve_object(self, obj: MibObject, seen: Set[str]) -> Optional[str]:
+        if obj.oid is not None or not obj.oid_parts or obj.name in seen:
+            return obj.oid
+        seen.add(obj.name)
+        head, *tail = obj.oid_parts
+        parent: Optional[str]
+        if isinstance(head, int):
+            parent = f".{head}"
+        elif head in self._objects:
+            parent = self._resolve_object(self._objects[head], seen)
+        else:
+            parent = _ROOTS.get(head)
+        if parent is None or not all(isinstance(part, int) for part in tail):
+            return None
+        obj.oid = "".join([parent] + [f".{part}" for part in tail])
+        return obj.oid
+
+
+class OidResolver:
+    __slots__ = ("_names", "_oids")
+
+    def __init__(self, mapping: Optional[Mapping[str, str]] = None) -> None:
+        self._names: Dict[str, str] = {}
+        self._oids: Dict[str, str] = {}
+        if mapping:
+            self.update(mapping)
+
+    @classmethod
+    def from_mib(cls, mib: Mib) -> "OidResolver":
+        return cls({obj.name: obj.oid for obj in mib if obj.oid is not None})
+
+    def __len__(self) -> int:
+        return len(self._oids)
+
+    def update(self, mapping: Mapping[str, str]) -> None:
+        for name, oid in mapping.items():
+            oid = f".{oid.lstrip('.')}"
+            self._names[oid] = name
+            self._oids[name] = oid
+
+    def resolve(self, oid: str) -> str:
+        # longest known prefix, the remaining arcs are kept as the instance suffix
+        oid = f".{oid.lstrip('.')}"
+        prefix = oid
+        while prefix:
+            name = self._names.get(prefix)
+            if name is not None:
+                return name + oid[len(prefix) :]
+            prefix = prefix.rpartition(".")[0]
+        return oid
+
+    def oid(self, name: str) -> str:
+        symbol, _, suffix = name.partition(".")
+        oid = self._oids.get(symbol)
+        if oid is None:
+            raise SnmpMibError(f"Unknown MIB object {symbol}")
+        return f"{oid}.{suffix}" if suffix else oid
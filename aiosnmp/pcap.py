@@ -0,0 +1,205 @@
+__all__ = ("LinkType", "PcapRecord", "read_pcap", "iter_frames", "udp_payload", "SNMP_PORTS")
+
+import enum
+import ipaddress
+import os
+import struct
+from typing import Any, Callable, FrozenSet, Iterable, Iterator, List, Optional, Tuple, Union
+
+from .message import parse_message
+
+SNMP_PORTS: FrozenSet[int] = frozenset((161, 162))
+
+Endpoint = Tuple[str, int]
+Frame = Tuple[float, int, bytes]
+
+_PCAP_MAGIC = {
+    b"\xd4\xc3\xb2\xa1": ("<", 1e-6),
+    b"\xa1\xb2\xc3\xd4": (">", 1e-6),
+    b"\x4d\x3c\xb2\xa1": ("<", 1e-9),
+    b"\xa1\xb2\x3c\x4d": (">", 1e-9),
+}
+_PCAPNG_SECTION_HEADER = 0x0A0D0D0A
+_PCAPNG_INTERFACE_DESCRIPTION = 0x00000001
+_PCAPNG_ENHANCED_PACKET = 0x00000006
+_PCAPNG_BYTE_ORDER_MAGIC = 0x1A2B3C4D
+_PCAPNG_IF_TSRESOL = 9
+
+_ETHERTYPE_IPV4 = 0x0800
+_ETHERTYPE_IPV6 = 0x86DD
+_ETHERTYPE_VLAN = (0x8100, 0x88A8, 0x9100)
+_IPV6_EXTENSION_HEADERS = (0, 43, 60)
+_IPPROTO_UDP = 17
+
+
+class LinkType(enum.IntEnum):
+    Null = 0
+    Ethernet = 1
+    Raw = 101
+    LinuxSLL = 113
+    IPv4 = 228
+    IPv6 = 229
+    LinuxSLL2 = 276
+
+
+class PcapRecord:
+    __slots__ = ("timestamp", "source", "destination", "message")
+
+    def __init__(self, timestamp: float, source: Endpoint, destination: Endpoint, message: Any) -> None:
+        self.timestamp: float = timestamp
+        self.source: Endpoint = source
+        self.destination: Endpoint = destination
+        self.message: Any = message
+
+
+def _iter_pcap(data: bytes) -> Iterator[Frame]:
+    endian, resolution = _PCAP_MAGIC[data[:4]]
+    (link_type,) = struct.unpack_from(f"{endian}I", data, 20)
+    offset = 24
+    while offset + 16 <= len(data):
+        seconds, fraction, captured, _ = struct.unpack_from(f"{endian}IIII", data, offset)
+        offset += 16
+        yield seconds + fraction * resolution, link_type & 0xFFFF, data[offset : offset + captured]
+        offset += captured
+
+
+def _interface_resolution(options: bytes, endian: str) -> float:
+    offset = 0
+    while offset + 4 <= len(options):
+        code, length = struct.unpack_from(f"{endian}HH", options, offset)
+        if code == 0:
+            break
+        if code == _PCAPNG_IF_TSRESOL and length >= 1:
+            value = options[offset + 4]
+            return float(2 ** -(value & 0x7F) if value & 0x80 else 10 ** -value)
+        offset += 4 + (length + 3) // 4 * 4
+    return 1e-6
+
+
+def _iter_pcapng(data: bytes) -> Iterator[Frame]:
+    endian = "<"
+    interfaces: List[Tuple[int, float]] = []
+    offset = 0
+    while offset + 12 <= len(data):
+        if struct.unpack_from("<I", data, offset)[0] == _PCAPNG_SECTION_HEADER:
+            magic = data[offset + 8 : offset + 12]
+            endian = "<" if struct.unpack("<I", magic)[0] == _PCAPNG_BYTE_ORDER_MAGIC else ">"
+            interfaces = []
+        block_type, block_length = struct.unpack_from(f"{endian}II", data, offset)
+        if block_length < 12:
+            raise ValueError(f"Invalid pcapng block length {block_length} at offset {offset}")
+        body = data[offset + 8 : offset + block_length - 4]
+        offset += block_length
+
+        if block_type == _PCAPNG_INTERFACE_DESCRIPTION:
+            (link_type,) = struct.unpack_from(f"{endian}H", body)
+            interfaces.append((link_type, _interface_resolution(body[8:], endian)))
+        elif block_type == _PCAPNG_ENHANCED_PACKET:
+            interface, high, low, captured, _ = struct.unpack_from(f"{endian}IIIII", body)
+            if interface >= len(interfaces):
+                raise ValueError(f"Unknown pcapng interface {interface} at offset {offset - block_length}")
+            link_type, resolution = interfaces[interface]
+            yield ((high << 32) | low) * resolution, link_type, body[20 : 20 + captured]
+
+
+def iter_frames(data: bytes) -> Iterator[Frame]:
+    # yields (timestamp, link type, frame) for every packet of a pcap or pcapng capture
+    if data[:4] in _PCAP_MAGIC:
+        return _iter_pcap(data)
+    if data[:4] == struct.pack("<I", _PCAPNG_SECTION_HEADER):
+        return _iter_pcapng(data)
+    raise ValueError("Not a pcap or pcapng capture")
+
+
+def _network_layer(frame: bytes, link_type: int) -> Tuple[Optional[int], bytes]:
+    if link_type == LinkType.Ethernet:
+        (ethertype,) = struct.unpack_from("!H", frame, 12)
+        offset = 14
+        while ethertype in _ETHERTYPE_VLAN:
+            (ethertype,) = struct.unpack_from("!H", frame, offset + 2)
+            offset += 4
+        return ethertype, frame[offset:]
+    if link_type == LinkType.LinuxSLL:
+        return struct.unpack_from("!H", frame, 14)[0], frame[16:]
+    if link_type == LinkType.LinuxSLL2:
+        return struct.unpack_from("!H", frame, 0)[0], frame[20:]
+    if link_type == LinkType.Null:
+        frame = frame[4:]
+    elif link_type not in (LinkType.Raw, LinkType.IPv4, LinkType.IPv6):
+        return None, b""
+    # the ip version is taken from the packet itself
+    version = frame[0] >> 4 if frame else 0
+    return {4: _ETHERTYPE_IPV4, 6: _ETHERTYPE_IPV6}.get(version), frame
+
+
+def _ipv4_udp(packet: bytes) -> Optional[Tuple[str, str, bytes]]:
+    header_length = (packet[0] & 0x0F) * 4
+    (flags_fragment,) = struct.unpack_from("!H", packet, 6)
+    # fragments are not reassembled
+    if packet[9] != _IPPROTO_UDP or flags_fragment & 0x3FFF:
+        return None
+    (total_length,) = struct.unpack_from("!H", packet, 2)
+    source = str(ipaddress.IPv4Address(packet[12:16]))
+    destination = str(ipaddress.IPv4Address(packet[16:20]))
+    return source, destination, packet[header_length:total_length]
+
+
+def _ipv6_udp(packet: bytes) -> Optional[Tuple[str, str, bytes]]:
+    next_header = packet[6]
+    offset = 40
+    while next_header in _IPV6_EXTENSION_HEADERS:
+        next_header = packet[offset]
+        offset += (packet[offset + 1] + 1) * 8
+    if next_header != _IPPROTO_UDP:
+        return None
+    source = str(ipaddress.IPv6Address(packet[8:24]))
+    destination = str(ipaddress.IPv6Address(packet[24:40]))
+    return source, destination, packet[offset:]
+
+
+def udp_payload(frame: bytes, link_type: int) -> Optional[Tuple[Endpoint, Endpoint, bytes]]:
+    # returns (source, destination, payload) of an UDP datagram or None for any other packet
+    try:
+        ethertype, packet = _network_layer(frame, link_type)
+        if ethertype == _ETHERTYPE_IPV4:
+            ip = _ipv4_udp(packet)
+        elif ethertype == _ETHERTYPE_IPV6:
+            ip = _ipv6_udp(packet)
+        else:
+            return None
+        if ip is None:
+            return None
+        source, destination, datagram = ip
+        source_port, destination_port, length = struct.unpack_from("!HHH", datagram)
+    except (struct.error, IndexError, ValueError):
+        # truncated packets
+        return None
+    return (source, source_port), (destination, destination_port), datagram[8:length]
+
+
+def read_pcap(
+    capture: Union[str, bytes, "os.PathLike[str]"],
+    *,
+    ports: Iterable[int] = SNMP_PORTS,
+    decode: Callable[[bytes], Any] = parse_message,
+) -> List[PcapRecord]:
+    # decode errors are returned in place of the message, like decode_many does
+    if not isinstance(capture, bytes):
+        with open(capture, "rb") as f:
+            capture = f.read()
+
+    ports = frozenset(ports)
+    records: List[PcapRecord] = []
+    for timestamp, link_type, frame in iter_frames(capture):
+        udp = udp_payload(frame, link_type)
+        if udp is None:
+            continue
+        source, destination, payload = udp
+        if source[1] not in ports and destination[1] not in ports:
+            continue
+        try:
+            message = decode(payload)
+        except Exception as exc:
+            message = exc
+        records.append(PcapRecord(timestamp, source, destination, message))
+    return records
@@ -0,0 +1,228 @@
+__all__ = (
+    "SaltGenerator",
+    "PrivProtocol",
+    "PrivParamsManager",
+    "des_iv",
+    "aes_iv",
+    "password_to_key",
+    "localize_key",
+    "des3_localize_key",
+    "des3_encrypt",
+    "des3_decrypt",
+    "LocalizedKeys",
+    "KeyCache",
+)
+
+import enum
+import hashlib
+import secrets
+import threading
+import time
+from typing import Any, Callable, Dict, NamedTuple, Optional, Tuple
+
+from .exceptions import SnmpPrivacyParametersReused
+
+_SALT_MASK = 0xFFFFFFFFFFFFFFFF
+
+
+class SaltGenerator:
+    __slots__ = ("_lock", "_counters", "_issued")
+
+    def __init__(self) -> None:
+        self._lock: threading.Lock = threading.Lock()
+        # engine id -> next 64 bit salt, every counter starts at a random value
+        self._counters: Dict[bytes, int] = {}
+        # (engine id, salt bits) -> (engine boots, salts issued since they changed)
+        self._issued: Dict[Tuple[bytes, int], Tuple[Optional[int], int]] = {}
+
+    def next(self, engine_id: bytes, bits: int = 64, engine_boots: Optional[int] = None) -> int:
+        # the low ``bits`` of the counter repeat after 2 ** bits salts, unless snmpEngineBoots changed
+        with self._lock:
+            salt = self._counters.get(engine_id)
+            if salt is None:
+                salt = secrets.randbits(64)
+            boots, issued = self._issued.get((engine_id, bits), (engine_boots, 0))
+            if boots != engine_boots:
+                issued = 0
+            elif issued >> bits:
+                raise SnmpPrivacyParametersReused(f"{bits} bit salts of engine {engine_id.hex()} are exhausted")
+            self._issued[(engine_id, bits)] = (engine_boots, issued + 1)
+            self._counters[engine_id] = (salt + 1) & _SALT_MASK
+        return salt & ((1 << bits) - 1)
+
+    def des_parameters(self, engine_id: bytes, engine_boots: int) -> bytes:
+        # RFC 3414 8.1.1.1: snmpEngineBoots followed by a 32 bit counter
+        salt = self.next(engine_id, 32, engine_boots)
+        return engine_boots.to_bytes(4, byteorder="big") + salt.to_bytes(4, byteorder="big")
+
+    def aes_parameters(self, engine_id: bytes) -> bytes:
+        # RFC 3826 3.1.2.1: the 64 bit salt itself
+        return self.next(engine_id).to_bytes(8, byteorder="big")
+
+    def forget(self, engine_id: bytes) -> None:
+        # after engine rediscovery the salts start over from a random value
+        with self._lock:
+            self._counters.pop(engine_id, None)
+            for key in [key for key in self._issued if key[0] == engine_id]:
+                del self._issued[key]
+
+
+class PrivProtocol(enum.Enum):
+    DES = "DES"
+    DES3 = "3DES"
+    AES = "AES"
+
+
+class PrivParamsManager:
+    __slots__ = ("salts",)
+
+    def __init__(self, salts: Optional[SaltGenerator] = None) -> None:
+        self.salts: SaltGenerator = salts if salts is not None else SaltGenerator()
+
+    def parameters(self, engine_id: bytes, protocol: PrivProtocol, engine_boots: int = 0) -> bytes:
+        # DES and 3DES salts are unique per snmpEngineBoots, AES salts over the whole 64 bit range
+        if protocol is PrivProtocol.AES:
+            return self.salts.aes_parameters(engine_id)
+        return self.salts.des_parameters(engine_id, engine_boots)
+
+    def forget(self, engine_id: bytes) -> None:
+        self.salts.forget(engine_id)
+
+
+def des_iv(privacy_key: bytes, privacy_parameters: bytes) -> bytes:
+    if len(privacy_key) < 16 or len(privacy_parameters) != 8:
+        raise ValueError("DES needs a 16 octets privacy key and 8 octets privacy parameters")
+    return bytes(a ^ b for a, b in zip(privacy_key[8:16], privacy_parameters))
+
+
+def aes_iv(engine_boots: int, engine_time: int, privacy_parameters: bytes) -> bytes:
+    if len(privacy_parameters) != 8:
+        raise ValueError("AES needs 8 octets privacy parameters")
+    return engine_boots.to_bytes(4, byteorder="big") + engine_time.to_bytes(4, byteorder="big") + privacy_parameters
+
+
+def password_to_key(password: bytes, hash_name: str) -> bytes:
+    # RFC 3414 A.2: digest of the password repeated over 1048576 octets
+    if not password:
+        raise ValueError("Password should not be empty")
+    repeated = password * (1_048_576 // len(password) + 1)
+    return hashlib.new(hash_name, repeated[:1_048_576]).digest()
+
+
+def localize_key(key: bytes, engine_id: bytes, hash_name: str) -> bytes:
+    return hashlib.new(hash_name, key + engine_id + key).digest()
+
+
+_DES3_KEY_LENGTH = 32
+
+
+def des3_localize_key(key: bytes, engine_id: bytes, hash_name: str) -> bytes:
+    # draft-reeder-snmpv3-usm-3desede 2.1: 24 octets of key and 8 octets of pre-IV, short digests
+    # are extended by localizing the password to key transformation of the previous block
+    localized = localize_key(key, engine_id, hash_name)
+    block = localized
+    while len(localized) < _DES3_KEY_LENGTH:
+        block = localize_key(password_to_key(block, hash_name), engine_id, hash_name)
+        localized += block
+    return localized[:_DES3_KEY_LENGTH]
+
+
+def _des3_cipher(privacy_key: bytes, privacy_parameters: bytes) -> Any:
+    try:
+        from cryptography.hazmat.primitives.ciphers import Cipher, modes
+    except ImportError:
+        raise RuntimeError("3DES privacy requires the cryptography package")
+    try:
+        from cryptography.hazmat.decrepit.ciphers.algorithms import TripleDES
+    except ImportError:
+        from cryptography.hazmat.primitives.ciphers.algorithms import TripleDES
+
+    if len(privacy_key) != _DES3_KEY_LENGTH or len(privacy_parameters) != 8:
+        raise ValueError("3DES needs a 32 octets localized key and 8 octets privacy parameters")
+    iv = bytes(a ^ b for a, b in zip(privacy_key[24:], privacy_parameters))
+    return Cipher(TripleDES(privacy_key[:24]), modes.CBC(iv))
+
+
+def des3_encrypt(privacy_key: bytes, privacy_parameters: bytes, data: bytes) -> bytes:
+    # the scoped pdu is BER encoded, so the receiver ignores the padding after it
+    data += bytes(-len(data) % 8)
+    encryptor = _des3_cipher(privacy_key, privacy_parameters).encryptor()
+    return bytes(encryptor.update(data) + encryptor.finalize())
+
+
+def des3_decrypt(privacy_key: bytes, privacy_parameters: bytes, data: bytes) -> bytes:
+    if len(data) % 8:
+        raise ValueError(f"Encrypted data length should be a multiple of 8, got {len(data)}")
+    decryptor = _des3_cipher(privacy_key, privacy_parameters).decryptor()
+    return bytes(decryptor.update(data) + decryptor.finalize())
+
+
+class LocalizedKeys(NamedTuple):
+    auth_key: bytes
+    priv_key: Optional[bytes]
+
+
+class KeyCache:
+    __slots__ = ("ttl", "_clock", "_entries")
+
+    def __init__(self, *, ttl: float = 3600.0, clock: Callable[[], float] = time.monotonic) -> None:
+        self.ttl: float = ttl
+        self._clock: Callable[[], float] = clock
+        # (user name, engine id) -> (localized keys, expiration timestamp)
+        self._entries: Dict[Tuple[bytes, bytes], Tuple[LocalizedKeys, float]] = {}
+
+    def __contains__(self, key: Tuple[bytes, bytes]) -> bool:
+        return self.get(*key) is not None
+
+    def __len__(self) -> int:
+        # expired entries are dropped first, so the length agrees with get()
+        now = self._clock()
+        for key, (_, expires) in list(self._entries.items()):
+            if now >= expires:
+                del self._entries[key]
+        return len(self._entries)
+
+    def get(self, username: bytes, engine_id: bytes) -> Optional[LocalizedKeys]:
+        entry = self._entries.get((username, engine_id))
+        if entry is None:
+            return None
+        keys, expires = entry
+        if self._clock() >= expires:
+            del self._entries[(username, engine_id)]
+            return None
+        return keys
+
+    def put(self, username: bytes, engine_id: bytes, keys: LocalizedKeys) -> None:
+        self._entries[(username, engine_id)] = (keys, self._clock() + self.ttl)
+
+    def localize(
+        self,
+        username: bytes,
+        engine_id: bytes,
+        hash_name: str,
+        auth_password: bytes,
+        priv_password: Optional[bytes] = None,
+        *,
+        des3: bool = False,
+    ) -> LocalizedKeys:
+        # returns the cached keys or localizes the passwords, the privacy key uses the authentication hash
+        keys = self.get(username, engine_id)
+        if keys is not None:
+            return keys
+        auth_key = localize_key(password_to_key(auth_password, hash_name), engine_id, hash_name)
+        priv_key: Optional[bytes] = None
+        if priv_password is not None:
+            key = password_to_key(priv_password, hash_name)
+            priv_key = des3_localize_key(key, engine_id, hash_name) if des3 else localize_key(key, engine_id, hash_name)
+        keys = LocalizedKeys(auth_key, priv_key)
+        self.put(username, engine_id, keys)
+        return keys
+
+    def invalidate(self, username: Optional[bytes] = None, engine_id: Optional[bytes] = None) -> None:
+        # drops the entries matching both arguments, None matches anything
+        for key in list(self._entries):
+            if (username is None or key[0] == username) and (engine_id is None or key[1] == engine_id):
+                del self._entries[key]
+
+    def clear(self) -> None:
+        self._entries.clear()
@@ -1,61 +1,61 @@
 import asyncio
-from typing import Callable, Dict, List, Optional, Set, Text, Tuple, Union, cast
+from collections import OrderedDict
+from typing import Callable, Dict, Hashable, List, Optional, Set, Text, Tuple, Union, cast
 
 from .asn1 import Error
-from .exceptions import (
-    SnmpErrorAuthorizationError,
-    SnmpErrorBadValue,
-    SnmpErrorCommitFailed,
-    SnmpErrorGenErr,
-    SnmpErrorInconsistentName,
-    SnmpErrorInconsistentValue,
-    SnmpErrorNoAccess,
-    SnmpErrorNoCreation,
-    SnmpErrorNoSuchName,
-    SnmpErrorNotWritable,
-    SnmpErrorReadOnly,
-    SnmpErrorResourceUnavailable,
-    SnmpErrorTooBig,
-    SnmpErrorUndoFailed,
-    SnmpErrorWrongEncoding,
-    SnmpErrorWrongLength,
-    SnmpErrorWrongType,
-    SnmpErrorWrongValue,
-    SnmpTimeoutError,
-)
+from .exceptions import SnmpTimeoutError
 from .log import logger
-from .message import PDU, SnmpMessage, SnmpResponse, SnmpV2TrapMessage, SnmpVarbind
-
-_ERROR_STATUS_TO_EXCEPTION = {
-    1: SnmpErrorTooBig,
-    2: SnmpErrorNoSuchName,
-    3: SnmpErrorBadValue,
-    4: SnmpErrorReadOnly,
-    5: SnmpErrorGenErr,
-    6: SnmpErrorNoAccess,
-    7: SnmpErrorWrongType,
-    8: SnmpErrorWrongLength,
-    9: SnmpErrorWrongEncoding,
-    10: SnmpErrorWrongValue,
-    11: SnmpErrorNoCreation,
-    12: SnmpErrorInconsistentValue,
-    13: SnmpErrorResourceUnavailable,
-    14: SnmpErrorCommitFailed,
-    15: SnmpErrorUndoFailed,
-    16: SnmpErrorAuthorizationError,
-    17: SnmpErrorNotWritable,
-    18: SnmpErrorInconsistentName,
-}
+from .message import (
+    PDU,
+    Community,
+    SnmpMessage,
+    SnmpResponse,
+    SnmpV2TrapMessage,
+    SnmpVarbind,
+    error_status_exception,
+    peek_request_id,
+)
 
 Address = Union[Tuple[str, int], Tuple[str, int, int, int]]
 
 
+class DuplicateCache:
+    __slots__ = ("maxsize", "_entries")
+
+    def __init__(self, maxsize: int = 1024) -> None:
+        self.maxsize: int = maxsize
+        # least recently added first
+        self._entries: "OrderedDict[Hashable, None]" = OrderedDict()
+
+    def __contains__(self, key: Hashable) -> bool:
+        return key in self._entries
+
+    def __len__(self) -> int:
+        return len(self._entries)
+
+    def add(self, key: Hashable) -> bool:
+        # returns False if the key was already seen
+        if key in self._entries:
+            self._entries.move_to_end(key)
+            return False
+        self._entries[key] = None
+        if len(self._entries) > self.maxsize:
+            self._entries.popitem(last=False)
+        return True
+
+    def discard(self, key: Hashable) -> None:
+        self._entries.pop(key, None)
+
+    def clear(self) -> None:
+        self._entries.clear()
+
+
 class SnmpTrapProtocol(asyncio.DatagramProtocol):
     __slots__ = ("loop", "transport", "communities", "handler")
 
-    def __init__(self, communities: Optional[Set[str]], handler: Callable) -> None:
+    def __init__(self, communities: Optional[Set[Community]], handler: Callable) -> None:
         self.loop: asyncio.AbstractEventLoop = asyncio.get_event_loop()
-        self.communities: Optional[Set[str]] = communities
+        self.communities: Optional[Set[Community]] = communities
         self.handler: Callable = handler
 
     def connection_made(self, transport: asyncio.BaseTransport) -> None:
@@ -80,11 +80,13 @@ class SnmpTrapProtocol(asyncio.DatagramProtocol):
 
 
 class SnmpProtocol(asyncio.DatagramProtocol):
-    __slots__ = ("loop", "transport", "requests", "timeout", "retries")
+    __slots__ = ("loop", "transport", "requests", "answered", "timeout", "retries")
 
     def __init__(self, timeout: float, retries: int) -> None:
         self.loop: asyncio.AbstractEventLoop = asyncio.get_event_loop()
         self.requests: Dict[Tuple[str, int, int], asyncio.Future] = {}
+        # (host, port, request id) of answered requests, retransmitted responses are dropped undecoded
+        self.answered: DuplicateCache = DuplicateCache()
         self.timeout: float = timeout
         self.retries: int = retries
 
@@ -99,20 +101,22 @@ class SnmpProtocol(asyncio.DatagramProtocol):
             return
 
         try:
+            key = (host, port, peek_request_id(data))
+            # late and duplicate responses are dropped without decoding them
+            if key not in self.requests or key in self.answered:
+                return
             message = SnmpResponse.decode(data)
         except Error as exc:
             logger.warning(f"could not decode received data from {host}:{port}: {exc}")
             return
 
-        key = (host, port, message.data.request_id)
         if key in self.requests:
             exception: Optional[Exception] = None
-            if isinstance(message.data, PDU) and message.data.error_status != 0:
-                index: int = message.data.error_index
-                oid = None
-                if len(message.data.varbinds) > 0 and index - 1 >= 0:
-                    oid = message.data.varbinds[index - 1].oid
-                exception = _ERROR_STATUS_TO_EXCEPTION[message.data.error_status](index, oid)
+            if isinstance(message.data, PDU):
+                exception = error_status_exception(
+                    message.data.error_status, message.data.error_index, message.data.varbinds
+                )
+            self.answered.add(key)
             try:
                 if exception:
                     self.requests[key].set_exception(exception)
@@ -130,6 +134,8 @@ class SnmpProtocol(asyncio.DatagramProtocol):
         fut: asyncio.Future = self.loop.create_future()
         fut.add_done_callback(lambda fn: self.requests.pop(key) if key in self.requests else None)
         self.requests[key] = fut
+        # request ids wrap around eventually
+        self.answered.discard(key)
         for _ in range(self.retries):
             self.transport.sendto(message.encode())
             done, _ = await asyncio.wait({fut}, timeout=self.timeout, return_when=asyncio.ALL_COMPLETED)
@@ -0,0 +1,285 @@
+__all__ = (
+    "TextualConvention",
+    "render",
+    "apply_textual_conventions",
+    "decode_bits",
+    "encode_bits",
+    "Syntax",
+    "SyntaxRegistry",
+    "IndexPart",
+    "decode_index",
+    "encode_index",
+)
+
+import datetime
+import enum
+import ipaddress
+from typing import Any, Dict, Iterable, List, Optional, Sequence, Set, Tuple, TypeVar, Union
+
+from .asn1 import Number
+from .exceptions import SnmpErrorWrongLength, SnmpErrorWrongType, SnmpErrorWrongValue
+from .message import SnmpVarbind
+
+_T = TypeVar("_T")
+
+
+class TextualConvention(enum.Enum):
+    DisplayString = "DisplayString"
+    DateAndTime = "DateAndTime"
+    TruthValue = "TruthValue"
+    MacAddress = "MacAddress"
+    PhysAddress = "PhysAddress"
+
+
+def _render_date_and_time(value: bytes) -> datetime.datetime:
+    if len(value) not in (8, 11):
+        raise ValueError(f"DateAndTime should be 8 or 11 octets, got {len(value)}")
+    year = int.from_bytes(value[:2], byteorder="big")
+    month, day, hour, minute, second, deci_seconds = value[2:8]
+    tz: Optional[datetime.tzinfo] = None
+    if len(value) == 11:
+        direction = chr(value[8])
+        if direction not in "+-":
+            raise ValueError(f"Invalid direction from UTC {direction!r}")
+        offset = datetime.timedelta(hours=value[9], minutes=value[10])
+        tz = datetime.timezone(offset if direction == "+" else -offset)
+    return datetime.datetime(year, month, day, hour, minute, second, deci_seconds * 100_000, tzinfo=tz)
+
+
+def render(value: Any, convention: TextualConvention) -> Any:
+    # values that do not match the convention are returned as is
+    try:
+        if convention == TextualConvention.DisplayString and isinstance(value, bytes):
+            return value.decode("utf-8", errors="replace")
+        if convention == TextualConvention.DateAndTime and isinstance(value, bytes):
+            return _render_date_and_time(value)
+        if convention == TextualConvention.MacAddress and isinstance(value, bytes) and len(value) == 6:
+            return ":".join(f"{byte:02x}" for byte in value)
+        if convention == TextualConvention.PhysAddress and isinstance(value, bytes):
+            return ":".join(f"{byte:02x}" for byte in value)
+        if convention == TextualConvention.TruthValue and value in (1, 2) and not isinstance(value, bool):
+            return value == 1
+    except ValueError:
+        pass
+    return value
+
+
+def _lookup(oid: str, mapping: Dict[str, _T]) -> Optional[_T]:
+    # mappings are keyed by object or column oids, instances carry the index after them
+    while oid:
+        value = mapping.get(oid)
+        if value is not None:
+            return value
+        oid = oid.rpartition(".")[0]
+    return None
+
+
+def apply_textual_conventions(varbinds: Iterable[SnmpVarbind], conventions: Dict[str, TextualConvention]) -> None:
+    conventions = {f".{oid.lstrip('.')}": convention for oid, convention in conventions.items()}
+    for varbind in varbinds:
+        convention = _lookup(varbind.oid, conventions)
+        if convention is not None:
+            varbind.value = render(varbind.value, convention)
+
+
+def decode_bits(value: bytes, names: Optional[Dict[int, str]] = None) -> Set[Union[int, str]]:
+    # BITS are numbered from the most significant bit of the first octet
+    names = names or {}
+    bits: Set[Union[int, str]] = set()
+    for i, byte in enumerate(value):
+        for j in range(8):
+            if byte & (0x80 >> j):
+                bit = i * 8 + j
+                bits.add(names.get(bit, bit))
+    return bits
+
+
+def encode_bits(bits: Iterable[Union[int, str]], names: Optional[Dict[int, str]] = None) -> bytes:
+    indices = {name: bit for bit, name in (names or {}).items()}
+    numbers = set()
+    for bit in bits:
+        if isinstance(bit, str):
+            if bit not in indices:
+                raise ValueError(f"Unknown bit name {bit!r}")
+            bit = indices[bit]
+        if bit < 0:
+            raise ValueError(f"Bit number should not be negative, got {bit}")
+        numbers.add(bit)
+    if not numbers:
+        return b""
+    value = bytearray(max(numbers) // 8 + 1)
+    for bit in numbers:
+        value[bit // 8] |= 0x80 >> (bit % 8)
+    return bytes(value)
+
+
+_PYTHON_TYPES: Dict[int, Tuple[type, ...]] = {
+    Number.Integer: (int,),
+    Number.OctetString: (str, bytes),
+    Number.ObjectIdentifier: (str,),
+    Number.IPAddress: (ipaddress.IPv4Address,),
+    Number.Counter32: (int,),
+    Number.Gauge32: (int,),
+    Number.TimeTicks: (int,),
+    Number.Counter64: (int,),
+    Number.Uinteger32: (int,),
+}
+
+
+class Syntax:
+    __slots__ = ("number", "ranges", "sizes", "values")
+
+    def __init__(
+        self,
+        number: int = Number.Integer,
+        *,
+        ranges: Optional[Sequence[Tuple[int, int]]] = None,
+        sizes: Optional[Sequence[Tuple[int, int]]] = None,
+        values: Optional[Iterable[int]] = None,
+    ) -> None:
+        if number not in _PYTHON_TYPES:
+            raise ValueError(f"Unsupported syntax {number!r}")
+        self.number: int = number
+        # inclusive (min, max) pairs, like INTEGER (1..10 | 20..30) and OCTET STRING (SIZE (0..255))
+        self.ranges: Optional[List[Tuple[int, int]]] = list(ranges) if ranges is not None else None
+        self.sizes: Optional[List[Tuple[int, int]]] = list(sizes) if sizes is not None else None
+        # enumerated INTEGER values
+        self.values: Optional[Set[int]] = set(values) if values is not None else None
+
+    def check(self, value: Any, index: int, oid: str) -> None:
+        if isinstance(value, bool) or not isinstance(value, _PYTHON_TYPES[self.number]):
+            raise SnmpErrorWrongType(index, oid)
+        if self.sizes is not None:
+            size = len(value.encode() if isinstance(value, str) else value)
+            if not any(low <= size <= high for low, high in self.sizes):
+                raise SnmpErrorWrongLength(index, oid)
+        if not isinstance(value, int):
+            return
+        if self.ranges is not None and not any(low <= value <= high for low, high in self.ranges):
+            raise SnmpErrorWrongValue(index, oid)
+        if self.values is not None and value not in self.values:
+            raise SnmpErrorWrongValue(index, oid)
+
+
+class SyntaxRegistry:
+    __slots__ = ("_syntaxes",)
+
+    def __init__(self, syntaxes: Optional[Dict[str, Syntax]] = None) -> None:
+        self._syntaxes: Dict[str, Syntax] = {}
+        for oid, syntax in (syntaxes or {}).items():
+            self.register(oid, syntax)
+
+    def register(self, oid: str, syntax: Syntax) -> None:
+        self._syntaxes[f".{oid.lstrip('.')}"] = syntax
+
+    def get(self, oid: str) -> Optional[Syntax]:
+        return _lookup(f".{oid.lstrip('.')}", self._syntaxes)
+
+    def validate(self, varbinds: Iterable[Tuple[str, Any]]) -> None:
+        # raises the error status an agent would respond with, error indexes start at 1 like in a response
+        for index, (oid, value) in enumerate(varbinds, 1):
+            syntax = self.get(oid)
+            if syntax is not None:
+                syntax.check(value, index, f".{oid.lstrip('.')}")
+
+    def varbinds(self, varbinds: Iterable[Tuple[str, Any]]) -> List[SnmpVarbind]:
+        # validated varbinds, typed by their syntax so that e.g. a Gauge32 is not sent as INTEGER
+        varbinds = list(varbinds)
+        self.validate(varbinds)
+        typed: List[SnmpVarbind] = []
+        for oid, value in varbinds:
+            syntax = self.get(oid)
+            typed.append(SnmpVarbind(oid, value, syntax.number if syntax is not None else None))
+        return typed
+
+
+class IndexPart:
+    __slots__ = ("number", "size", "implied")
+
+    def __init__(self, number: int = Number.Integer, *, size: Optional[int] = None, implied: bool = False) -> None:
+        if number not in (Number.Integer, Number.OctetString, Number.ObjectIdentifier, Number.IPAddress):
+            raise ValueError(f"Unsupported index syntax {number!r}")
+        self.number: int = number
+        # fixed size OCTET STRING, encoded without the length sub-identifier
+        self.size: Optional[int] = size
+        # IMPLIED last index object, its length is the rest of the suffix
+        self.implied: bool = implied
+
+
+def _sub_identifiers(suffix: Union[str, Sequence[int]]) -> List[int]:
+    if isinstance(suffix, str):
+        return [int(arc) for arc in suffix.strip(".").split(".")] if suffix.strip(".") else []
+    return list(suffix)
+
+
+def _index_length(part: IndexPart, arcs: List[int], offset: int, last: bool) -> Tuple[int, int]:
+    # returns the offset of the value and the count of its sub-identifiers
+    if part.number == Number.Integer:
+        return offset, 1
+    if part.number == Number.IPAddress:
+        return offset, 4
+    if part.size is not None:
+        return offset, part.size
+    if part.implied:
+        if not last:
+            raise ValueError("Only the last index object can be IMPLIED")
+        return offset, len(arcs) - offset
+    if offset >= len(arcs):
+        raise ValueError("Index suffix is too short")
+    return offset + 1, arcs[offset]
+
+
+def decode_index(suffix: Union[str, Sequence[int]], parts: Sequence[IndexPart]) -> List[Any]:
+    # splits the instance suffix after a column oid into values of its INDEX objects
+    arcs = _sub_identifiers(suffix)
+    values: List[Any] = []
+    offset = 0
+    for i, part in enumerate(parts):
+        offset, count = _index_length(part, arcs, offset, i == len(parts) - 1)
+        value = arcs[offset : offset + count]
+        if len(value) != count:
+            raise ValueError("Index suffix is too short")
+        offset += count
+        if part.number == Number.Integer:
+            values.append(value[0])
+        elif part.number == Number.ObjectIdentifier:
+            values.append("." + ".".join(map(str, value)))
+        elif any(arc > 0xFF for arc in value):
+            raise ValueError(f"Index octet out of range in {value}")
+        elif part.number == Number.IPAddress:
+            values.append(ipaddress.IPv4Address(bytes(value)))
+        else:
+            values.append(bytes(value))
+    if offset != len(arcs):
+        raise ValueError(f"{len(arcs) - offset} unused sub-identifiers in index suffix")
+    return values
+
+
+def _encode_index_value(value: Any, part: IndexPart) -> List[int]:
+    if part.number == Number.Integer:
+        return [int(value)]
+    if part.number == Number.IPAddress:
+        return list(ipaddress.IPv4Address(value).packed)
+    if part.number == Number.ObjectIdentifier:
+        arcs = _sub_identifiers(value)
+    else:
+        arcs = list(value.encode() if isinstance(value, str) else value)
+    if part.size is not None:
+        if len(arcs) != part.size:
+            raise ValueError(f"Index value should be {part.size} octets, got {len(arcs)}")
+        return arcs
+    if part.implied:
+        return arcs
+    return [len(arcs)] + arcs
+
+
+def encode_index(values: Sequence[Any], parts: Sequence[IndexPart]) -> str:
+    # builds the instance suffix to append to a column oid
+    if len(values) != len(parts):
+        raise ValueError(f"Expected {len(parts)} index values, got {len(values)}")
+    arcs: List[int] = []
+    for i, (value, part) in enumerate(zip(values, parts)):
+        if part.implied and i != len(parts) - 1:
+            raise ValueError("Only the last index object can be IMPLIED")
+        arcs.extend(_encode_index_value(value, part))
+    return "".join(f".{arc}" for arc in arcs)
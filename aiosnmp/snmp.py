@@ -7,26 +7,38 @@ from typing import Any, List, Optional, Tuple, Type, Union
 
 from .connection import SnmpConnection
 from .exceptions import SnmpUnsupportedValueType
-from .message import GetBulkRequest, GetNextRequest, GetRequest, SetRequest, SnmpMessage, SnmpVarbind, SnmpVersion
+from .message import (
+    Community,
+    GetBulkRequest,
+    GetNextRequest,
+    GetRequest,
+    SetRequest,
+    SnmpMessage,
+    SnmpVarbind,
+    SnmpVersion,
+)
+from .smi import SyntaxRegistry
 
 
 class Snmp(SnmpConnection):
-    __slots__ = ("version", "community", "non_repeaters", "max_repetitions")
+    __slots__ = ("version", "community", "non_repeaters", "max_repetitions", "syntaxes")
 
     def __init__(
         self,
         *,
         version: SnmpVersion = SnmpVersion.v2c,
-        community: str = "public",
+        community: Community = "public",
         non_repeaters: int = 0,
         max_repetitions: int = 10,
+        syntaxes: Optional[SyntaxRegistry] = None,
         **kwargs: Any,
     ) -> None:
         super().__init__(**kwargs)
         self.version: SnmpVersion = version
-        self.community: str = community
+        self.community: Community = community
         self.non_repeaters: int = non_repeaters
         self.max_repetitions: int = max_repetitions
+        self.syntaxes: Optional[SyntaxRegistry] = syntaxes
 
     def __enter__(self) -> "Snmp":
         warnings.warn("Use async with, this is deprecated", FutureWarning)
@@ -124,11 +136,11 @@ class Snmp(SnmpConnection):
         for varbind in varbinds:
             if not isinstance(varbind[1], (int, str, bytes, ipaddress.IPv4Address)):
                 raise SnmpUnsupportedValueType(f"Only int, str, bytes and ip address supported, got {type(varbind[1])}")
-        message = SnmpMessage(
-            self.version,
-            self.community,
-            SetRequest([SnmpVarbind(oid, value) for oid, value in varbinds]),
-        )
+        if self.syntaxes is not None:
+            vbs = self.syntaxes.varbinds(varbinds)
+        else:
+            vbs = [SnmpVarbind(oid, value) for oid, value in varbinds]
+        message = SnmpMessage(self.version, self.community, SetRequest(vbs))
         return await self._send(message)
 
     async def bulk_walk(
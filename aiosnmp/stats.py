@@ -0,0 +1,85 @@
+__all__ = ("Stats", "stats", "reset_stats", "enable_stats")
+
+import collections
+import threading
+from typing import Any, Counter, Dict, Optional
+
+
+class Stats:
+    __slots__ = (
+        "_lock",
+        "enabled",
+        "messages_decoded",
+        "messages_encoded",
+        "bytes_decoded",
+        "bytes_encoded",
+        "errors",
+        "pdu_types",
+    )
+
+    def __init__(self, enabled: bool = False) -> None:
+        self._lock = threading.Lock()
+        # counting is off by default, so the decode and encode paths only pay for a flag check
+        self.enabled: bool = enabled
+        self.messages_decoded: int = 0
+        self.messages_encoded: int = 0
+        self.bytes_decoded: int = 0
+        self.bytes_encoded: int = 0
+        # exception class name -> count
+        self.errors: Counter[str] = collections.Counter()
+        # PDU type name -> count, for both directions
+        self.pdu_types: Counter[str] = collections.Counter()
+
+    def add_decoded(self, size: int, pdu_type: Optional[str]) -> None:
+        with self._lock:
+            self.messages_decoded += 1
+            self.bytes_decoded += size
+            if pdu_type is not None:
+                self.pdu_types[pdu_type] += 1
+
+    def add_encoded(self, size: int, pdu_type: Optional[str]) -> None:
+        with self._lock:
+            self.messages_encoded += 1
+            self.bytes_encoded += size
+            if pdu_type is not None:
+                self.pdu_types[pdu_type] += 1
+
+    def add_error(self, size: int, exc: BaseException) -> None:
+        with self._lock:
+            self.bytes_decoded += size
+            self.errors[type(exc).__name__] += 1
+
+    def snapshot(self) -> Dict[str, Any]:
+        with self._lock:
+            return {
+                "messages_decoded": self.messages_decoded,
+                "messages_encoded": self.messages_encoded,
+                "bytes_decoded": self.bytes_decoded,
+                "bytes_encoded": self.bytes_encoded,
+                "errors": dict(self.errors),
+                "pdu_types": dict(self.pdu_types),
+            }
+
+    def reset(self) -> None:
+        with self._lock:
+            self.messages_decoded = 0
+            self.messages_encoded = 0
+            self.bytes_decoded = 0
+            self.bytes_encoded = 0
+            self.errors.clear()
+            self.pdu_types.clear()
+
+
+_stats = Stats()
+
+
+def stats() -> Dict[str, Any]:
+    return _stats.snapshot()
+
+
+def reset_stats() -> None:
+    _stats.reset()
+
+
+def enable_stats(enabled: bool = True) -> None:
+    _stats.enabled = enabled
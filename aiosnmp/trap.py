@@ -1,10 +1,11 @@
-__all__ = ("SnmpV2TrapServer",)
+__all__ = ("SnmpV2TrapServer", "receive_many")
 
 import asyncio
-from typing import Callable, Iterable, Optional, Set, Tuple, cast
+import socket
+from typing import Any, Callable, Iterable, List, Optional, Set, Tuple, cast
 
-from .message import SnmpV2TrapMessage
-from .protocols import SnmpTrapProtocol
+from .message import Community, SnmpV2TrapMessage, _decode_community, decode_many, parse_trap
+from .protocols import Address, SnmpTrapProtocol
 
 
 async def _default_handler(host: str, port: int, message: SnmpV2TrapMessage) -> None:
@@ -20,13 +21,14 @@ class SnmpV2TrapServer:
         host: str = "0.0.0.0",
         port: int = 162,
         handler: Callable = _default_handler,
-        communities: Optional[Iterable[str]] = None,
+        communities: Optional[Iterable[Community]] = None,
     ) -> None:
         self.host: str = host
         self.port: int = port
-        self.communities: Optional[Set[str]] = None
+        self.communities: Optional[Set[Community]] = None
         if communities is not None:
-            self.communities = set(communities)
+            # compared with communities as decoded from messages, utf-8 bytes become str
+            self.communities = {_decode_community(c) if isinstance(c, bytes) else c for c in communities}
         self.handler: Callable = handler
 
     async def run(self) -> Tuple[asyncio.BaseTransport, SnmpTrapProtocol]:
@@ -37,3 +39,27 @@ class SnmpV2TrapServer:
             local_addr=(self.host, self.port),
         )
         return transport, cast(SnmpTrapProtocol, protocol)
+
+
+def receive_many(
+    sock: socket.socket,
+    *,
+    max_count: int = 64,
+    bufsize: int = 65535,
+    decode: Callable[[bytes], Any] = parse_trap,
+) -> List[Tuple[Address, Any]]:
+    # drains up to max_count queued datagrams from a non-blocking socket and decodes them as one batch,
+    # python has no recvmmsg so this saves the event loop round trip per datagram instead of the syscall
+    if sock.gettimeout() != 0.0:
+        # a blocking socket would wait for further datagrams instead of returning the queued ones
+        raise ValueError("receive_many needs a non-blocking socket")
+    datagrams: List[bytes] = []
+    addresses: List[Address] = []
+    while len(datagrams) < max_count:
+        try:
+            data, addr = sock.recvfrom(bufsize)
+        except (BlockingIOError, InterruptedError):
+            break
+        datagrams.append(data)
+        addresses.append(addr)
+    return list(zip(addresses, decode_many(datagrams, decode)))
@@ -0,0 +1,231 @@
+__all__ = (
+    "ReportCounter",
+    "Discovery",
+    "classify_report",
+    "report_exception",
+    "encode_discovery_request",
+    "parse_discovery_report",
+    "reauthenticate",
+    "verify_authentication",
+)
+
+import enum
+import hmac
+from typing import Dict, List, NamedTuple, Optional, Tuple, Type
+
+from .asn1 import Asn1SyntaxError, Class, Decoder, Encoder, Field, Number
+from .engine import EngineTimeCache
+from .exceptions import (
+    SnmpInvalidEngineId,
+    SnmpReport,
+    SnmpReportDecryptionError,
+    SnmpReportInvalidMsg,
+    SnmpReportNotInTimeWindow,
+    SnmpReportUnavailableContext,
+    SnmpReportUnknownContext,
+    SnmpReportUnknownEngineId,
+    SnmpReportUnknownPDUHandler,
+    SnmpReportUnknownSecurityModel,
+    SnmpReportUnknownUserName,
+    SnmpReportUnsupportedSecLevel,
+    SnmpReportWrongDigest,
+)
+from .message import GetRequest, PDUType, Report, SnmpVarbind, SnmpVersion, _decode_pdu
+
+MSG_MAX_SIZE = 65507
+_MSG_FLAG_AUTH = 0x01
+_MSG_FLAG_PRIV = 0x02
+_MSG_FLAG_REPORTABLE = 0x04
+_USM_SECURITY_MODEL = 3
+
+_GLOBAL_DATA = (
+    Field("msg_id", Number.Integer),
+    Field("max_size", Number.Integer),
+    Field("flags", Number.OctetString),
+    Field("security_model", Number.Integer),
+)
+_SECURITY_PARAMETERS = (
+    Field("engine_id", Number.OctetString),
+    Field("boots", Number.Integer),
+    Field("time", Number.Integer),
+    Field("user_name", Number.OctetString),
+    Field("auth_parameters", Number.OctetString),
+    Field("priv_parameters", Number.OctetString),
+)
+
+
+class ReportCounter(enum.Enum):
+    UnknownSecurityModels = ".1.3.6.1.6.3.11.2.1.1.0"
+    InvalidMsgs = ".1.3.6.1.6.3.11.2.1.2.0"
+    UnknownPDUHandlers = ".1.3.6.1.6.3.11.2.1.3.0"
+    UnavailableContexts = ".1.3.6.1.6.3.12.1.4.0"
+    UnknownContexts = ".1.3.6.1.6.3.12.1.5.0"
+    UnsupportedSecLevels = ".1.3.6.1.6.3.15.1.1.1.0"
+    NotInTimeWindows = ".1.3.6.1.6.3.15.1.1.2.0"
+    UnknownUserNames = ".1.3.6.1.6.3.15.1.1.3.0"
+    UnknownEngineIDs = ".1.3.6.1.6.3.15.1.1.4.0"
+    WrongDigests = ".1.3.6.1.6.3.15.1.1.5.0"
+    DecryptionErrors = ".1.3.6.1.6.3.15.1.1.6.0"
+
+
+_REPORT_COUNTER_TO_EXCEPTION: Dict[ReportCounter, Type[SnmpReport]] = {
+    ReportCounter.UnknownSecurityModels: SnmpReportUnknownSecurityModel,
+    ReportCounter.InvalidMsgs: SnmpReportInvalidMsg,
+    ReportCounter.UnknownPDUHandlers: SnmpReportUnknownPDUHandler,
+    ReportCounter.UnavailableContexts: SnmpReportUnavailableContext,
+    ReportCounter.UnknownContexts: SnmpReportUnknownContext,
+    ReportCounter.UnsupportedSecLevels: SnmpReportUnsupportedSecLevel,
+    ReportCounter.NotInTimeWindows: SnmpReportNotInTimeWindow,
+    ReportCounter.UnknownUserNames: SnmpReportUnknownUserName,
+    ReportCounter.UnknownEngineIDs: SnmpReportUnknownEngineId,
+    ReportCounter.WrongDigests: SnmpReportWrongDigest,
+    ReportCounter.DecryptionErrors: SnmpReportDecryptionError,
+}
+
+_OID_TO_REPORT_COUNTER: Dict[str, ReportCounter] = {counter.value: counter for counter in ReportCounter}
+
+
+def classify_report(varbinds: List[SnmpVarbind]) -> Optional[ReportCounter]:
+    for varbind in varbinds:
+        counter = _OID_TO_REPORT_COUNTER.get(varbind.oid)
+        if counter is not None:
+            return counter
+    return None
+
+
+def report_exception(varbinds: List[SnmpVarbind]) -> Optional[SnmpReport]:
+    if not varbinds:
+        return None
+    counter = classify_report(varbinds)
+    if counter is None:
+        varbind = varbinds[0]
+        return SnmpReport(varbind.oid, varbind.value if isinstance(varbind.value, int) else None)
+    value = next(vb.value for vb in varbinds if vb.oid == counter.value)
+    return _REPORT_COUNTER_TO_EXCEPTION[counter](counter.value, value if isinstance(value, int) else None)
+
+
+class Discovery(NamedTuple):
+    engine_id: bytes
+    boots: int
+    time: int
+    msg_id: int
+    # usually UnknownEngineIDs
+    report: Optional[ReportCounter]
+
+
+def encode_discovery_request(msg_id: int, request_id: Optional[int] = None, *, max_size: int = MSG_MAX_SIZE) -> bytes:
+    # RFC 3414 4: reportable noAuthNoPriv get with empty engine id, user name and varbinds
+    security_parameters = Encoder()
+    security_parameters.write_sequence([b"", 0, 0, b"", b"", b""])
+    pdu = GetRequest([])
+    if request_id is not None:
+        pdu.request_id = request_id
+
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(SnmpVersion.v3, Number.Integer)
+        with encoder.enter(Number.Sequence):
+            encoder.write(msg_id, Number.Integer)
+            encoder.write(max_size, Number.Integer)
+            encoder.write(bytes((_MSG_FLAG_REPORTABLE,)), Number.OctetString)
+            encoder.write(_USM_SECURITY_MODEL, Number.Integer)
+        encoder.write(security_parameters.output(), Number.OctetString)
+        with encoder.enter(Number.Sequence):
+            encoder.write(b"", Number.OctetString)
+            encoder.write(b"", Number.OctetString)
+            pdu.encode(encoder)
+    return encoder.output()
+
+
+def parse_discovery_report(data: bytes, cache: Optional[EngineTimeCache] = None) -> Discovery:
+    # boots and time are 0 in reports to an unauthenticated discovery, they are cached anyway
+    # so the next authenticated request gets a notInTimeWindow report with the real values
+    decoder = Decoder(data)
+    with decoder.enter():
+        version = decoder.read_integer()
+        if version != SnmpVersion.v3:
+            raise Asn1SyntaxError(f"Expected a SNMPv3 message, got version {version}")
+        global_data = decoder.read_struct(_GLOBAL_DATA)
+        if global_data["security_model"] != _USM_SECURITY_MODEL:
+            raise Asn1SyntaxError(f"Unsupported security model {global_data['security_model']}")
+        flags = global_data["flags"]
+        if flags and flags[0] & _MSG_FLAG_PRIV:
+            raise Asn1SyntaxError("Discovery reports should not be encrypted")
+        security_parameters = Decoder(decoder.read_octet_string()).read_struct(_SECURITY_PARAMETERS)
+
+        with decoder.enter():
+            decoder.read_octet_string()
+            decoder.read_octet_string()
+            tag = decoder.peek()
+            if tag.cls != Class.Context or tag.nr != PDUType.Report:
+                raise Asn1SyntaxError(f"Expected a Report PDU, got {tag!r}")
+            pdu = _decode_pdu(decoder, Report)
+
+    engine_id = security_parameters["engine_id"]
+    if not engine_id:
+        raise SnmpInvalidEngineId("Report has an empty authoritative engine id")
+    boots = security_parameters["boots"]
+    engine_time = security_parameters["time"]
+    if cache is not None:
+        cache.update(engine_id, boots, engine_time)
+    return Discovery(engine_id, boots, engine_time, global_data["msg_id"], classify_report(pdu.varbinds))
+
+
+def _element_size(decoder: Decoder) -> int:
+    header = decoder.peek_header()
+    return header.size + header.length
+
+
+def _auth_parameters_span(data: bytes) -> Tuple[int, int]:
+    # offset and length of the msgAuthenticationParameters content octets within the message
+    decoder = Decoder(data)
+    offset = decoder.peek_header().size
+    with decoder.enter():
+        offset += _element_size(decoder)
+        version = decoder.read_integer()
+        if version != SnmpVersion.v3:
+            raise Asn1SyntaxError(f"Expected a SNMPv3 message, got version {version}")
+        offset += _element_size(decoder)
+        global_data = decoder.read_struct(_GLOBAL_DATA)
+        if global_data["security_model"] != _USM_SECURITY_MODEL:
+            raise Asn1SyntaxError(f"Unsupported security model {global_data['security_model']}")
+        flags = global_data["flags"]
+        if not flags or not flags[0] & _MSG_FLAG_AUTH:
+            raise ValueError("Message is not authenticated")
+        offset += decoder.peek_header().size
+        security_parameters = Decoder(decoder.read_octet_string())
+
+    offset += security_parameters.peek_header().size
+    with security_parameters.enter():
+        # msgAuthoritativeEngineID, msgAuthoritativeEngineBoots, msgAuthoritativeEngineTime, msgUserName
+        for _ in range(4):
+            offset += _element_size(security_parameters)
+            security_parameters.skip()
+        header = security_parameters.peek_header()
+        if header.tag.nr != Number.OctetString or header.tag.cls != Class.Universal:
+            raise Asn1SyntaxError(f"Expected msgAuthenticationParameters, got {header.tag!r}")
+    return offset + header.size, header.length
+
+
+def _digest(message: bytearray, start: int, length: int, auth_key: bytes, hash_name: str) -> bytes:
+    # RFC 3414 6.3.1 and RFC 7860 4.2.1: HMAC over the message with zeroed parameters, truncated
+    message[start : start + length] = bytes(length)
+    digest = hmac.new(auth_key, message, hash_name).digest()
+    if not 0 < length <= len(digest):
+        raise ValueError(f"Invalid msgAuthenticationParameters length {length} for {hash_name}")
+    return digest[:length]
+
+
+def reauthenticate(data: bytes, auth_key: bytes, hash_name: str) -> bytes:
+    # replaces the digest of an encoded message with one computed with auth_key, e.g. in a proxy
+    # that forwards a message to another engine, without decoding and encoding the message again
+    start, length = _auth_parameters_span(data)
+    message = bytearray(data)
+    message[start : start + length] = _digest(message, start, length, auth_key, hash_name)
+    return bytes(message)
+
+
+def verify_authentication(data: bytes, auth_key: bytes, hash_name: str) -> bool:
+    start, length = _auth_parameters_span(data)
+    received = data[start : start + length]
+    return hmac.compare_digest(_digest(bytearray(data), start, length, auth_key, hash_name), received)
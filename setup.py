@@ -17,6 +17,7 @@ setup(
     description="asyncio SNMP client",
     long_description=readme.read_text("utf-8"),
     long_description_content_type="text/markdown",
+    extras_require={"privacy": ["cryptography"]},
     setup_requires=["pytest-runner"],
     tests_require=["pytest"],
     classifiers=[
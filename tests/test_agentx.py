@@ -0,0 +1,165 @@
+import asyncio
+import ipaddress
+
+import pytest
+
+from aiosnmp.agentx import (
+    AgentXClose,
+    AgentXError,
+    AgentXFlags,
+    AgentXGet,
+    AgentXGetBulk,
+    AgentXGetNext,
+    AgentXNotify,
+    AgentXOpen,
+    AgentXPDU,
+    AgentXPing,
+    AgentXRegister,
+    AgentXResponse,
+    AgentXTestSet,
+    AgentXUnregister,
+    CloseReason,
+    SearchRange,
+    decode_header,
+    decode_pdu,
+    encode_pdu,
+    read_pdu,
+)
+from aiosnmp.asn1 import Number
+from aiosnmp.message import SnmpVarbind
+
+
+def test_encode_ping() -> None:
+    pdu = AgentXPing()
+    pdu.session_id, pdu.transaction_id, pdu.packet_id = 1, 2, 3
+    assert encode_pdu(pdu) == bytes.fromhex("010d1000 00000001 00000002 00000003 00000000")
+    assert encode_pdu(pdu, network_byte_order=False) == bytes.fromhex("010d0000 01000000 02000000 03000000 00000000")
+
+
+def test_encode_register() -> None:
+    pdu = AgentXRegister(".1.3.6.1.4.1.8072", timeout=5, range_subid=7, upper_bound=10)
+    pdu.flags = AgentXFlags.InstanceRegistration
+    pdu.context = b"ctx"
+    assert encode_pdu(pdu) == bytes.fromhex(
+        "01031900 00000000 00000000 00000000 0000001c"
+        "00000003 63747800"
+        "057f0700"
+        "02040000 00000001 00001f88"
+        "0000000a"
+    )
+
+
+def test_encode_oid_without_prefix() -> None:
+    pdu = AgentXGet([SearchRange(".1.3.6.1.256", include=True), SearchRange(".1.2")])
+    assert encode_pdu(pdu)[20:] == bytes.fromhex(
+        "05000100 00000001 00000003 00000006 00000001 00000100"
+        "00000000"
+        "02000000 00000001 00000002"
+        "00000000"
+    )
+
+
+@pytest.mark.parametrize(
+    "pdu",
+    (
+        AgentXOpen(5, ".1.3.6.1.4.1.8072.3.2", b"subagent"),
+        AgentXClose(CloseReason.ByManager),
+        AgentXRegister(".1.3.6.1.2.1.1", priority=1),
+        AgentXUnregister(".1.3.6.1.2.1.1.5.0"),
+        AgentXGet([SearchRange(".1.3.6.1.2.1.1.1.0")]),
+        AgentXGetNext([SearchRange(".1.3.6.1.2.1.1", ".1.3.6.1.2.1.2", include=True)]),
+        AgentXGetBulk(1, 10, [SearchRange(".1.3.6.1.2.1.1.3"), SearchRange(".1.3.6.1.2.1.2.2.1.2")]),
+        AgentXPing(),
+        AgentXTestSet([SnmpVarbind(".1.3.6.1.2.1.1.5.0", b"host")]),
+        AgentXNotify(
+            [
+                SnmpVarbind(".1.3.6.1.2.1.1.3.0", 100, Number.TimeTicks),
+                SnmpVarbind(".1.3.6.1.6.3.1.1.4.1.0", ".1.3.6.1.6.3.1.1.5.1", Number.ObjectIdentifier),
+            ]
+        ),
+        AgentXResponse(
+            1234,
+            AgentXError.NoAgentXError,
+            0,
+            [
+                SnmpVarbind(".1.3.6.1.2.1.1.1.0", -5),
+                SnmpVarbind(".1.3.6.1.2.1.1.2.0", b"abcde", Number.Opaque),
+                SnmpVarbind(".1.3.6.1.2.1.1.3.0", 2 ** 32 - 1, Number.Gauge32),
+                SnmpVarbind(".1.3.6.1.2.1.1.4.0", 2 ** 64 - 1, Number.Counter64),
+                SnmpVarbind(".1.3.6.1.2.1.1.5.0", ipaddress.IPv4Address("10.0.0.1")),
+                SnmpVarbind(".1.3.6.1.2.1.1.6.0", None, Number.NoSuchInstance),
+                SnmpVarbind(".1.3.6.1.2.1.1.7.0"),
+            ],
+        ),
+    ),
+)
+@pytest.mark.parametrize("network_byte_order", (True, False))
+def test_roundtrip(pdu: AgentXPDU, network_byte_order: bool) -> None:
+    pdu.session_id, pdu.transaction_id, pdu.packet_id = 7, 8, 9
+    data = encode_pdu(pdu, network_byte_order=network_byte_order)
+    decoded = decode_pdu(data)
+    assert type(decoded) is type(pdu)
+    assert (decoded.session_id, decoded.transaction_id, decoded.packet_id) == (7, 8, 9)
+    assert encode_pdu(decoded, network_byte_order=network_byte_order) == data
+
+    header = decode_header(data)
+    assert header.pdu_type == pdu._PDUType
+    assert header.payload_length == len(data) - 20
+
+
+def test_decode_response() -> None:
+    pdu = AgentXResponse(10, AgentXError.DuplicateRegistration, 1, [SnmpVarbind(".1.3.6.1.2.1.1.1.0", b"test")])
+    decoded = decode_pdu(encode_pdu(pdu))
+    assert isinstance(decoded, AgentXResponse)
+    assert (decoded.sys_uptime, decoded.error, decoded.index) == (10, AgentXError.DuplicateRegistration, 1)
+    assert [(vb.oid, vb.value, vb.number) for vb in decoded.varbinds] == [
+        (".1.3.6.1.2.1.1.1.0", b"test", Number.OctetString)
+    ]
+
+
+def test_decode_context() -> None:
+    pdu = AgentXGet([SearchRange(".1.3.6.1.2.1.1.1.0")])
+    pdu.context = b"vrf"
+    decoded = decode_pdu(encode_pdu(pdu))
+    assert decoded.context == b"vrf"
+    assert decoded.flags == 0
+
+
+@pytest.mark.parametrize(
+    "data",
+    (
+        bytes.fromhex("010d1000 00000000 00000000"),
+        bytes.fromhex("020d1000 00000000 00000000 00000000 00000000"),
+        bytes.fromhex("01ff1000 00000000 00000000 00000000 00000000"),
+        bytes.fromhex("010d1000 00000000 00000000 00000000 00000004"),
+        bytes.fromhex("01021000 00000000 00000000 00000000 00000002 0500"),
+        bytes.fromhex("010d1000 00000000 00000000 00000000 00000004 00000000"),
+        bytes.fromhex("01121000 00000000 00000000 00000000 0000000c 00000000 00000000 00ff0000"),
+    ),
+)
+def test_decode_error(data: bytes) -> None:
+    with pytest.raises(ValueError):
+        decode_pdu(data)
+
+
+def test_encode_error() -> None:
+    with pytest.raises(ValueError):
+        encode_pdu(AgentXTestSet([SnmpVarbind(".1.3.6.1.2.1.1.1.0", "text", Number.Integer)]))
+    with pytest.raises(ValueError):
+        encode_pdu(AgentXClose(256))
+
+
+def test_read_pdu() -> None:
+    async def read() -> AgentXPDU:
+        reader = asyncio.StreamReader()
+        reader.feed_data(encode_pdu(AgentXPing()) + encode_pdu(AgentXClose()))
+        await read_pdu(reader)
+        return await read_pdu(reader)
+
+    loop = asyncio.new_event_loop()
+    try:
+        pdu = loop.run_until_complete(read())
+    finally:
+        loop.close()
+    assert isinstance(pdu, AgentXClose)
+    assert pdu.reason == CloseReason.Shutdown
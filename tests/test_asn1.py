@@ -5,7 +5,11 @@
 # Python-ASN1 is copyright (c) 2007-2016 by the Python-ASN1 authors. See the
 # file "AUTHORS" for a complete overview.
 
+import copy
 import ipaddress
+import pickle
+import sys
+from typing import Any, NamedTuple
 
 import pytest
 
@@ -209,6 +213,278 @@ class TestEncoder:
         with pytest.raises(asn1.Error):
             enc.write(value, asn1.Number.ObjectIdentifier)
 
+    def test_reset(self) -> None:
+        enc = asn1.Encoder()
+        enc.write(1)
+        enc.reset()
+        enc.write(2)
+        assert enc.output() == b"\x02\x01\x02"
+        enc.reset()
+        assert enc.output() == b""
+
+    def test_capacity(self) -> None:
+        enc = asn1.Encoder(capacity=64)
+        buffer = enc.m_buffer
+        with enc.enter(asn1.Number.Sequence):
+            enc.write(1)
+            enc.write(b"foo")
+        assert enc.output() == b"\x30\x08\x02\x01\x01\x04\x03foo"
+        assert enc.m_buffer is buffer and len(buffer) == 64
+
+        # the preallocated octets are overwritten after a reset, without growing the buffer
+        enc.reset()
+        enc.write(b"ab")
+        assert enc.output() == b"\x04\x02ab"
+        enc.write(b"x" * 100)
+        assert enc.output() == b"\x04\x02ab\x04\x64" + b"x" * 100
+        assert len(enc.m_buffer) == 106
+
+    def test_reserve(self) -> None:
+        enc = asn1.Encoder()
+        enc.write(1)
+        enc.reserve(300)
+        assert len(enc.m_buffer) == 303
+        enc.reserve(10)
+        assert len(enc.m_buffer) == 303
+        enc.write(b"x" * 296)
+        assert len(enc.m_buffer) == 303
+        assert enc.output() == b"\x02\x01\x01\x04\x82\x01\x28" + b"x" * 296
+
+    def test_capacity_discard(self) -> None:
+        enc = asn1.Encoder(capacity=32)
+        with pytest.raises(ValueError):
+            with enc.enter(asn1.Number.Sequence):
+                enc.write(b"abc")
+                raise ValueError
+        enc.write(None)
+        assert enc.output() == b"\x05\x00"
+        assert pickle.loads(pickle.dumps(enc)).output() == b"\x05\x00"
+
+    def test_output_into(self) -> None:
+        enc = asn1.Encoder()
+        with enc.enter(asn1.Number.Sequence):
+            enc.write(1)
+            enc.write(b"foo")
+        buf = bytearray(16)
+        size = enc.output_into(buf)
+        assert size == 10
+        assert bytes(buf[:size]) == enc.output()
+        view = memoryview(bytearray(16))[4:]
+        assert enc.output_into(view) == 10
+        assert bytes(view[:10]) == enc.output()
+
+    @pytest.mark.parametrize("buf", (bytearray(2), memoryview(b"\x00" * 16)))
+    def test_error_output_into(self, buf: bytearray) -> None:
+        enc = asn1.Encoder()
+        enc.write(b"foo")
+        with pytest.raises(asn1.Error):
+            enc.output_into(buf)
+
+    def test_write_raw(self) -> None:
+        enc = asn1.Encoder()
+        with enc.enter(asn1.Number.Sequence):
+            enc.write(1)
+            enc.write_raw(b"\x04\x03foo")
+        res = enc.output()
+        assert res == b"\x30\x08\x02\x01\x01\x04\x03foo"
+
+    def test_enter_exception(self) -> None:
+        enc = asn1.Encoder()
+        enc.write(1)
+        with pytest.raises(asn1.Error):
+            with enc.enter(asn1.Number.Sequence):
+                enc.write(2)
+                enc.write(1.1)
+        enc.write(3)
+        res = enc.output()
+        assert res == b"\x02\x01\x01\x02\x01\x03"
+
+    def test_encode_error(self) -> None:
+        enc = asn1.Encoder()
+        with pytest.raises(asn1.EncodeError):
+            enc.write(1.1)
+
+    def test_encode_error_message_context(self) -> None:
+        enc = asn1.Encoder()
+        with enc.enter(asn1.Number.Sequence):
+            with pytest.raises(asn1.EncodeError) as exc_info:
+                enc.write("1", asn1.Number.ObjectIdentifier)
+        assert exc_info.value.depth == 1
+        assert str(exc_info.value) == "Illegal object identifier (tag: Universal 0x06, depth: 1)"
+
+    def test_explicit(self) -> None:
+        enc = asn1.Encoder()
+        enc.write(5, explicit=0)
+        enc.write(b"ab", asn1.Number.OctetString, explicit=3)
+        assert enc.output() == b"\xa0\x03\x02\x01\x05\xa3\x04\x04\x02ab"
+
+    def test_explicit_high_tag_number(self) -> None:
+        enc = asn1.Encoder()
+        enc.write(None, explicit=40)
+        assert enc.output() == b"\xbf\x28\x02\x05\x00"
+
+    def test_set_of(self) -> None:
+        enc = asn1.Encoder()
+        enc.write_set_of([256, 3, -1, b"a"])
+        assert enc.output() == b"\x31\x0d\x02\x01\x03\x02\x01\xff\x02\x02\x01\x00\x04\x01a"
+
+    def test_set_of_explicit_number(self) -> None:
+        enc = asn1.Encoder()
+        enc.write_set_of(["b", "a"], asn1.Number.PrintableString)
+        enc.write_set_of([])
+        assert enc.output() == b"\x31\x06\x13\x01a\x13\x01b\x31\x00"
+
+    def test_sequence(self) -> None:
+        enc = asn1.Encoder()
+        enc.write([1, (b"a", None), []])
+        assert enc.output() == b"\x30\x0c\x02\x01\x01\x30\x05\x04\x01a\x05\x00\x30\x00"
+
+    def test_write_sequence(self) -> None:
+        enc = asn1.Encoder()
+        enc.write_sequence(iter([1, 2]))
+        assert enc.output() == b"\x30\x06\x02\x01\x01\x02\x01\x02"
+
+    @pytest.mark.parametrize("clone", (copy.copy, copy.deepcopy, lambda obj: pickle.loads(pickle.dumps(obj))))
+    def test_clone(self, clone: Any) -> None:
+        enc = asn1.Encoder()
+        enc.write(1)
+        other = clone(enc)
+        other.write(2)
+        assert enc.output() == b"\x02\x01\x01"
+        assert other.output() == b"\x02\x01\x01\x02\x01\x02"
+
+    @pytest.mark.parametrize("clone", (copy.copy, copy.deepcopy, lambda obj: pickle.loads(pickle.dumps(obj))))
+    def test_clone_unfinished(self, clone: Any) -> None:
+        enc = asn1.Encoder()
+        with enc.enter(asn1.Number.Sequence):
+            enc.write(1)
+            other = clone(enc)
+            other.write(2)
+        assert enc.output() == b"\x30\x03\x02\x01\x01"
+        with pytest.raises(asn1.EncodeError):
+            other.output()
+
+    @pytest.mark.parametrize(
+        ("value", "nr", "result"),
+        (
+            ("café", asn1.Number.UTF8String, b"\x0c\x05caf\xc3\xa9"),
+            ("1 2", asn1.Number.NumericString, b"\x12\x031 2"),
+            ("foo", asn1.Number.IA5String, b"\x16\x03foo"),
+            ("foo", asn1.Number.VisibleString, b"\x1a\x03foo"),
+            ("c\u0410", asn1.Number.BMPString, b"\x1e\x04\x00c\x04\x10"),
+        ),
+    )
+    def test_character_strings(self, value: str, nr: asn1.Number, result: bytes) -> None:
+        enc = asn1.Encoder()
+        enc.write(value, nr)
+        assert enc.output() == result
+        assert asn1.Decoder(result).read()[1] == value
+
+    def test_error_bmp_string(self) -> None:
+        enc = asn1.Encoder()
+        with pytest.raises(asn1.EncodeError):
+            enc.write("\U0001f600", asn1.Number.BMPString)
+
+    @pytest.mark.parametrize(
+        ("value", "nr", "result"),
+        (
+            (0, asn1.Number.Counter64, b"\x46\x01\x00"),
+            (2 ** 63 - 1, asn1.Number.Counter64, b"\x46\x08\x7f" + b"\xff" * 7),
+            (2 ** 63, asn1.Number.Counter64, b"\x46\x09\x00\x80" + b"\x00" * 7),
+            (2 ** 64 - 1, asn1.Number.Counter64, b"\x46\x09\x00" + b"\xff" * 8),
+            (2 ** 32 - 1, asn1.Number.Counter32, b"\x41\x05\x00\xff\xff\xff\xff"),
+            (2 ** 31, asn1.Number.Gauge32, b"\x42\x05\x00\x80\x00\x00\x00"),
+            (12345, asn1.Number.TimeTicks, b"\x43\x02\x30\x39"),
+            (7, asn1.Number.Uinteger32, b"\x47\x01\x07"),
+        ),
+    )
+    def test_unsigned(self, value: int, nr: asn1.Number, result: bytes) -> None:
+        enc = asn1.Encoder()
+        enc.write(value, nr)
+        assert enc.output() == result
+        tag, val = asn1.Decoder(result, strict=True).read()
+        assert tag == asn1.Tag(nr & 0x1F, cls=asn1.Class.Application)
+        assert val == value
+
+    @pytest.mark.parametrize(
+        ("value", "nr"),
+        ((-1, asn1.Number.Counter64), (2 ** 64, asn1.Number.Counter64), (2 ** 32, asn1.Number.Counter32)),
+    )
+    def test_error_unsigned(self, value: int, nr: asn1.Number) -> None:
+        enc = asn1.Encoder()
+        with pytest.raises(asn1.EncodeError):
+            enc.write(value, nr)
+
+    def test_max_size(self) -> None:
+        enc = asn1.Encoder(max_size=10)
+        with enc.enter(asn1.Number.Sequence):
+            enc.write(1)
+            enc.write(b"foo")
+        assert enc.output() == b"\x30\x08\x02\x01\x01\x04\x03foo"
+        enc.write(None)
+        with pytest.raises(asn1.MessageTooBigError):
+            enc.output()
+        with pytest.raises(asn1.MessageTooBigError):
+            enc.output_into(bytearray(16))
+        assert issubclass(asn1.MessageTooBigError, asn1.EncodeError)
+
+    @pytest.mark.parametrize("clone", (copy.copy, lambda obj: pickle.loads(pickle.dumps(obj))))
+    def test_max_size_clone(self, clone: Any) -> None:
+        enc = clone(asn1.Encoder(max_size=2))
+        enc.write(1)
+        with pytest.raises(asn1.MessageTooBigError):
+            enc.output()
+
+    def test_nested_long_lengths(self) -> None:
+        enc = asn1.Encoder()
+        with enc.enter(asn1.Number.Sequence):
+            with enc.enter(asn1.Number.Sequence):
+                enc.write(b"x" * 300)
+            enc.write(1)
+        inner = b"\x04\x82\x01\x2c" + b"x" * 300
+        outer = b"\x30\x82\x01\x30" + inner + b"\x02\x01\x01"
+        assert enc.output() == b"\x30\x82\x01\x37" + outer
+
+    @staticmethod
+    def _nested_tlv(depth: int, value: bytes) -> bytes:
+        # encodes each level on its own and concatenates it into the parent, like the former encoder
+        data = b""
+        for _ in range(depth):
+            content = asn1.Encoder._encode_length(len(value)) + value + data
+            data = b"\x30" + asn1.Encoder._encode_length(len(content) + 1) + b"\x04" + content
+        return data
+
+    @pytest.mark.parametrize("depth", (1, 2, 40))
+    def test_deep_nested_long_lengths(self, depth: int) -> None:
+        value = bytes(range(200))
+
+        def nest(level: int) -> None:
+            if level == depth:
+                return
+            with enc.enter(asn1.Number.Sequence):
+                enc.write(value)
+                nest(level + 1)
+
+        enc = asn1.Encoder()
+        nest(0)
+        expected = self._nested_tlv(depth, value)
+        assert enc.output() == expected
+        buffer = bytearray(len(expected) + 3)
+        assert enc.output_into(buffer) == len(expected)
+        assert buffer[: len(expected)] == expected
+        assert len(pickle.loads(pickle.dumps(enc)).output()) == len(expected)
+
+    def test_nested_long_lengths_discarded(self) -> None:
+        enc = asn1.Encoder()
+        with enc.enter(asn1.Number.Sequence):
+            with pytest.raises(ValueError):
+                with enc.enter(asn1.Number.Sequence):
+                    with enc.enter(asn1.Number.Sequence):
+                        enc.write(b"x" * 300)
+                    raise ValueError
+            enc.write(b"y" * 200)
+        assert enc.output() == b"\x30\x81\xcb\x04\x81\xc8" + b"y" * 200
+
 
 class TestDecoder:
     @pytest.mark.parametrize(
@@ -587,6 +863,690 @@ class TestDecoder:
         assert val == -668929531791034950848739021124816874
         assert dec.eof()
 
+    def test_reset(self) -> None:
+        dec = asn1.Decoder(b"\x30\x03\x02\x01\x01")
+        dec.peek()
+        dec.reset(b"\x02\x01\x02")
+        tag, val = dec.read()
+        assert val == 2
+        assert dec.eof()
+        dec.reset(b"\x04\x03foo")
+        tag, val = dec.read()
+        assert val == b"foo"
+        assert dec.eof()
+
+    def test_skip(self) -> None:
+        buf = b"\x30\x06\x02\x01\x01\x02\x01\x02\x04\x03foo\x02\x01\x03"
+        dec = asn1.Decoder(buf)
+        tag = dec.skip()
+        assert tag == (asn1.Number.Sequence, asn1.Type.Constructed, asn1.Class.Universal)
+        tag = dec.skip()
+        assert tag == (asn1.Number.OctetString, asn1.Type.Primitive, asn1.Class.Universal)
+        tag, val = dec.read()
+        assert val == 3
+        assert dec.eof()
+        with pytest.raises(asn1.Error):
+            dec.skip()
+
+    def test_error_skip_missing_value_bytes(self) -> None:
+        dec = asn1.Decoder(b"\x04\x05foo")
+        with pytest.raises(asn1.Error):
+            dec.skip()
+
+    def test_read_raw(self) -> None:
+        buf = b"\x30\x0a\x02\x01\x01\x3f\x83\x01\x03foo\x02\x01\x02"
+        dec = asn1.Decoder(buf)
+        with dec.enter():
+            assert dec.read_raw() == b"\x02\x01\x01"
+            dec.peek()
+            assert dec.read_raw() == b"\x3f\x83\x01\x03foo"
+            assert dec.eof()
+        dec.reset(buf)
+        assert dec.read_raw() == buf[:12]
+        tag, val = dec.read()
+        assert val == 2
+
+    @pytest.mark.parametrize(
+        ("buf", "length", "size"),
+        ((b"\x02\x01\x01", 1, 2), (b"\x04\x81\x80" + b"x" * 0x80, 0x80, 3), (b"\x3f\x83\x01\x03foo", 3, 4)),
+    )
+    def test_peek_header(self, buf: bytes, length: int, size: int) -> None:
+        dec = asn1.Decoder(buf)
+        header = dec.peek_header()
+        assert header.length == length
+        assert header.size == size
+        assert header.tag == dec.peek()
+        assert dec.peek_header() == header
+        assert dec.read_raw() == buf
+
+    def test_error_peek_header_missing_length_bytes(self) -> None:
+        dec = asn1.Decoder(b"\x04\x82\xff")
+        with pytest.raises(asn1.Error):
+            dec.peek_header()
+
+    def test_tell_seek(self) -> None:
+        buf = b"\x30\x06\x02\x01\x01\x02\x01\x02"
+        dec = asn1.Decoder(buf)
+        assert dec.tell() == 0
+        with dec.enter():
+            assert dec.tell() == 0
+            tag, val = dec.read()
+            offset = dec.tell()
+            assert offset == 3
+            dec.peek()
+            assert dec.tell() == offset
+            tag, val = dec.read()
+            assert val == 2
+            dec.seek(offset)
+            tag, val = dec.read(asn1.Number.OctetString)
+            assert val == b"\x02"
+            dec.seek(0)
+            tag, val = dec.read()
+            assert val == 1
+            with pytest.raises(asn1.Error):
+                dec.seek(7)
+        assert dec.tell() == len(buf)
+
+    def test_read_all(self) -> None:
+        buf = b"\x30\x0a\x02\x01\x01\x31\x05\x04\x01a\x05\x00\x01\x01\xff"
+        dec = asn1.Decoder(buf)
+        assert dec.read_all() == [
+            (
+                (asn1.Number.Sequence, asn1.Type.Constructed, asn1.Class.Universal),
+                [
+                    ((asn1.Number.Integer, asn1.Type.Primitive, asn1.Class.Universal), 1),
+                    (
+                        (asn1.Number.Set, asn1.Type.Constructed, asn1.Class.Universal),
+                        [
+                            ((asn1.Number.OctetString, asn1.Type.Primitive, asn1.Class.Universal), b"a"),
+                            ((asn1.Number.Null, asn1.Type.Primitive, asn1.Class.Universal), None),
+                        ],
+                    ),
+                ],
+            ),
+            ((asn1.Number.Boolean, asn1.Type.Primitive, asn1.Class.Universal), True),
+        ]
+        assert dec.eof()
+
+    def test_enter_exception(self) -> None:
+        buf = b"\x30\x03\x02\x02\x01\x02\x01\x02"
+        dec = asn1.Decoder(buf)
+        with pytest.raises(asn1.Error):
+            with dec.enter():
+                dec.read()
+        tag, val = dec.read()
+        assert val == 2
+        assert dec.eof()
+
+    def test_max_depth(self) -> None:
+        buf = b"\x30\x04\x30\x02\x05\x00"
+        dec = asn1.Decoder(buf, max_depth=2)
+        assert dec.read_all()[0][1][0][1][0][1] is None
+        dec = asn1.Decoder(buf, max_depth=1)
+        with dec.enter():
+            with pytest.raises(asn1.LimitError):
+                with dec.enter():
+                    pass
+
+    def test_max_length(self) -> None:
+        dec = asn1.Decoder(b"\x04\x03foo\x04\x04barr", max_length=3)
+        tag, val = dec.read()
+        assert val == b"foo"
+        with pytest.raises(asn1.LimitError):
+            dec.read()
+        dec.reset(b"\x30\x04\x04\x02ab")
+        with pytest.raises(asn1.LimitError):
+            dec.skip()
+
+    @pytest.mark.parametrize(
+        "buf",
+        (b"\x04\x81\x03foo", b"\x04\x82\x00\x03foo", b"\x04\x82\x00\x80" + b"x" * 0x80),
+    )
+    def test_strict_non_minimal_length(self, buf: bytes) -> None:
+        dec = asn1.Decoder(buf)
+        dec.read()
+        assert dec.eof()
+        dec = asn1.Decoder(buf, strict=True)
+        with pytest.raises(asn1.Error) as exc_info:
+            dec.read()
+        assert exc_info.value.offset == 0
+
+    @pytest.mark.parametrize("buf", (b"\x04\x80", b"\x30\x80\x02\x01\x01\x00\x00"))
+    def test_error_indefinite_length(self, buf: bytes) -> None:
+        # rejected like by message_boundaries(), for which the tag is not incomplete either
+        with pytest.raises(asn1.DecodeError):
+            asn1.Decoder(buf).read()
+        with pytest.raises(asn1.DecodeError):
+            asn1.Decoder(buf).peek_header()
+        with pytest.raises(asn1.DecodeError):
+            asn1.message_boundaries(buf)
+        with pytest.raises(asn1.DecodeError):
+            asn1.split_messages(buf)
+
+    @pytest.mark.parametrize(
+        "buf",
+        (b"\x04\x03foo", b"\x04\x81\x80" + b"x" * 0x80, b"\x04\x82\x01\x00" + b"x" * 0x100),
+    )
+    def test_strict_minimal_length(self, buf: bytes) -> None:
+        dec = asn1.Decoder(buf, strict=True)
+        dec.read()
+        assert dec.eof()
+
+    @pytest.mark.parametrize("buf", (b"\x02\x00", b"\x41\x00", b"\x46\x00"))
+    def test_error_empty_integer(self, buf: bytes) -> None:
+        dec = asn1.Decoder(buf)
+        with pytest.raises(asn1.Error):
+            dec.read()
+
+    @pytest.mark.parametrize(("buf", "result"), ((b"\x02\x02\x00\x01", 1), (b"\x02\x02\xff\xff", -1)))
+    def test_strict_non_minimal_integer(self, buf: bytes, result: int) -> None:
+        tag, val = asn1.Decoder(buf).read()
+        assert val == result
+        dec = asn1.Decoder(buf, strict=True)
+        with pytest.raises(asn1.Error) as exc_info:
+            dec.read()
+        assert exc_info.value.offset == 0
+
+    @pytest.mark.parametrize("buf", (b"\x01\x01\x01", b"\x01\x01\x7f"))
+    def test_strict_boolean(self, buf: bytes) -> None:
+        tag, val = asn1.Decoder(buf).read()
+        assert val is True
+        dec = asn1.Decoder(buf, strict=True)
+        with pytest.raises(asn1.Asn1SyntaxError) as exc_info:
+            dec.read()
+        assert exc_info.value.offset == 0
+
+    @pytest.mark.parametrize(("buf", "result"), ((b"\x01\x01\xff", True), (b"\x01\x01\x00", False)))
+    def test_strict_der_boolean(self, buf: bytes, result: bool) -> None:
+        tag, val = asn1.Decoder(buf, strict=True).read()
+        assert val is result
+
+    @pytest.mark.parametrize(
+        ("buf", "result"),
+        ((b"\x02\x02\x00\x80", 128), (b"\x02\x02\xff\x7f", -129), (b"\x46\x09\x00" + b"\xff" * 8, 2 ** 64 - 1)),
+    )
+    def test_strict_minimal_integer(self, buf: bytes, result: int) -> None:
+        tag, val = asn1.Decoder(buf, strict=True).read()
+        assert val == result
+
+    @pytest.mark.parametrize(
+        ("buf", "message"),
+        (
+            (b"\x04\x89" + b"\x01" * 9, "does not fit 64 bits"),
+            (b"\x04\x88" + b"\xff" * 8 + b"foo", "exceeds the remaining 3 octets"),
+            (b"\x30\x05\x04\x04foo", "exceeds the remaining 3 octets"),
+        ),
+    )
+    def test_error_length_overflow(self, buf: bytes, message: str) -> None:
+        dec = asn1.Decoder(buf)
+        with pytest.raises(asn1.Error, match=message):
+            dec.read_all()
+
+    @pytest.mark.parametrize(
+        ("buf", "result"),
+        (
+            (b"\x02\x00", 0),
+            (b"\x43\x00", 0),
+            (b"\x05\x01\x00", None),
+            (b"\x06\x00", ""),
+            (b"\x06\x02\x8c\x40", ".2.1520"),
+        ),
+    )
+    def test_lenient(self, buf: bytes, result: Any) -> None:
+        dec = asn1.Decoder(buf, lenient=True)
+        tag, val = dec.read()
+        assert val == result
+        with pytest.raises(asn1.Error):
+            asn1.Decoder(buf).read()
+
+    def test_ignore_trailing(self) -> None:
+        buf = b"\x30\x03\x02\x01\x01\x00\x00\x00"
+        dec = asn1.Decoder(buf)
+        dec.read()
+        assert not dec.eof()
+        assert dec.trailing_bytes == 0
+        dec = asn1.Decoder(buf, ignore_trailing=True)
+        assert dec.trailing_bytes == 3
+        with dec.enter():
+            tag, val = dec.read()
+            assert val == 1
+        assert dec.eof()
+        dec.reset(b"\x02\x01\x01")
+        assert dec.trailing_bytes == 0
+        dec.reset(b"\x02\x01\x01\x02")
+        assert dec.trailing_bytes == 1
+
+    @pytest.mark.parametrize(
+        ("buf", "exc_type", "offset", "depth"),
+        (
+            (b"\x30\x04\x02\x02\x01", asn1.PrematureEndError, 2, 0),
+            (b"\x30\x04\x02\x03\x01\x01", asn1.PrematureEndError, 4, 1),
+            (b"\x30\x04\x01\x02\x01\x01", asn1.Asn1SyntaxError, 2, 1),
+            (b"\x30\x03\x04\xff\x01", asn1.Asn1SyntaxError, 4, 1),
+        ),
+    )
+    def test_error_context(self, buf: bytes, exc_type: type, offset: int, depth: int) -> None:
+        dec = asn1.Decoder(buf)
+        with pytest.raises(exc_type) as exc_info:
+            dec.read_all()
+        assert isinstance(exc_info.value, asn1.DecodeError)
+        assert exc_info.value.offset == offset
+        assert exc_info.value.depth == depth
+
+    def test_error_context_tag(self) -> None:
+        dec = asn1.Decoder(b"\x30\x06\x02\x01\x01\x05\x01\x00")
+        with pytest.raises(asn1.Asn1SyntaxError) as exc_info:
+            dec.read_all()
+        assert exc_info.value.tag == (asn1.Number.Null, asn1.Type.Primitive, asn1.Class.Universal)
+        assert exc_info.value.offset == 5
+
+    def test_error_message_context(self) -> None:
+        dec = asn1.Decoder(b"\x30\x06\x02\x01\x01\x05\x01\x00")
+        with pytest.raises(asn1.Error) as exc_info:
+            dec.read_all()
+        assert str(exc_info.value) == "ASN1 syntax error (tag: Universal 0x05, offset: 5, depth: 1)"
+
+    @pytest.mark.parametrize(
+        "expect",
+        (asn1.Number.Integer, asn1.Tag(asn1.Number.Integer), asn1.Tag.universal(asn1.Number.Integer)),
+    )
+    def test_read_expect(self, expect: Any) -> None:
+        dec = asn1.Decoder(b"\x02\x01\x01\x43\x01\x02")
+        tag, val = dec.read(expect=expect)
+        assert val == 1
+        with pytest.raises(asn1.UnexpectedTagError) as exc_info:
+            dec.read(expect=expect)
+        assert exc_info.value.offset == 3
+        tag, val = dec.read(expect=asn1.Number.TimeTicks)
+        assert val == 2
+
+    def test_read_expect_message(self) -> None:
+        dec = asn1.Decoder(b"\x04\x01a")
+        with pytest.raises(asn1.UnexpectedTagError, match="Expected Integer, got Tag.nr=OctetString"):
+            dec.read(expect=asn1.Number.Integer)
+
+    def test_typed_readers(self) -> None:
+        dec = asn1.Decoder(b"\x02\x01\x2a\x04\x03foo\x06\x03\x2b\x06\x01\x05\x00")
+        assert dec.read_integer() == 42
+        assert dec.read_octet_string() == b"foo"
+        assert dec.read_oid() == ".1.3.6.1"
+        assert dec.read_null() is None
+        assert dec.eof()
+
+    def test_error_typed_readers(self) -> None:
+        dec = asn1.Decoder(b"\x04\x03foo")
+        for method in (dec.read_integer, dec.read_oid, dec.read_null):
+            with pytest.raises(asn1.UnexpectedTagError):
+                method()
+        assert dec.read_octet_string() == b"foo"
+
+    def test_hexdump(self) -> None:
+        dec = asn1.Decoder(b"\x30\x14\x04\x12" + b"0123456789abcdef\x00\xff")
+        with dec.enter():
+            dec.peek()
+            assert dec.hexdump().splitlines() == [
+                "00000002  04 12 30 31 32 33 34 35 36 37 38 39 61 62 63 64  |..0123456789abcd|",
+                "00000012  65 66 00 ff                                      |ef..|",
+            ]
+            assert dec.hexdump(limit=2) == "00000002  04 12" + " " * 44 + "|..|"
+            dec.read()
+            assert dec.hexdump() == ""
+
+    @pytest.mark.parametrize("clone", (copy.copy, copy.deepcopy, lambda obj: pickle.loads(pickle.dumps(obj))))
+    def test_clone(self, clone: Any) -> None:
+        dec = asn1.Decoder(memoryview(b"\x30\x06\x02\x01\x01\x02\x01\x02"), strict=True)
+        with dec.enter():
+            assert dec.read() == (asn1.Tag(asn1.Number.Integer), 1)
+            other = clone(dec)
+            assert dec.read() == (asn1.Tag(asn1.Number.Integer), 2)
+            assert dec.eof()
+        assert other.m_strict
+        assert other.read() == (asn1.Tag(asn1.Number.Integer), 2)
+        assert other.eof()
+
+    @pytest.mark.parametrize(
+        ("buf", "result"),
+        (
+            (b"\x04\x05hello", "hello"),
+            (b"\x04\x03a\tb", "a\tb"),
+            (b"\x04\x00", ""),
+            (b"\x04\x03\x00\x1b\xff", "0x001BFF"),
+            (b"\x04\x02a\x00", "0x6100"),
+        ),
+    )
+    def test_hex_strings(self, buf: bytes, result: str) -> None:
+        assert asn1.Decoder(buf, hex_strings=True).read()[1] == result
+        assert asn1.Decoder(memoryview(buf), hex_strings=True).read()[1] == result
+
+    @pytest.mark.parametrize(
+        ("buf", "result"),
+        (
+            (b"\x04\x08eth0\x00\x00  ", b"eth0"),
+            (b"\x04\x05eth0 ", b"eth0"),
+            (b"\x04\x04 a \x00", b" a"),
+            (b"\x04\x06\x00\x11\x22\x33\x44\x00", b"\x00\x11\x22\x33\x44\x00"),
+            (b"\x04\x02\x00\x00", b"\x00\x00"),
+            (b"\x0c\x04ab\x00\x00", "ab"),
+            (b"\x13\x03ab ", "ab"),
+        ),
+    )
+    def test_strip_padding(self, buf: bytes, result: Any) -> None:
+        assert asn1.Decoder(buf, strip_padding=True).read()[1] == result
+
+    def test_strip_padding_hex_strings(self) -> None:
+        assert asn1.Decoder(b"\x04\x04ab\x00\x00", strip_padding=True, hex_strings=True).read()[1] == "ab"
+        dec = asn1.Decoder(b"\x04\x03ab\x00", lazy=True, strip_padding=True)
+        tag, value = dec.read()
+        assert dec.decode_value(tag, value) == b"ab"
+
+    @pytest.mark.parametrize(
+        ("buf", "result"),
+        (
+            (b"\x0c\x04caf\xc3", None),
+            (b"\x0c\x05caf\xc3\xa9", "café"),
+            (b"\x12\x031 2", "1 2"),
+            (b"\x16\x03foo", "foo"),
+            (b"\x1a\x03foo", "foo"),
+            (b"\x1e\x04\x00c\x04\x10", "c\u0410"),
+            (b"\x1e\x03\x00c\x04", None),
+        ),
+    )
+    def test_character_strings(self, buf: bytes, result: Any) -> None:
+        dec = asn1.Decoder(buf)
+        if result is None:
+            with pytest.raises(asn1.Asn1SyntaxError):
+                dec.read()
+        else:
+            assert dec.read()[1] == result
+
+    def test_lazy(self) -> None:
+        dec = asn1.Decoder(b"\x30\x0b\x06\x03\x2b\x06\x01\x41\x01\x05\x02\x01\x07", lazy=True)
+        with dec.enter():
+            tag, value = dec.read()
+            assert tag == asn1.Tag(asn1.Number.ObjectIdentifier)
+            assert value == b"\x2b\x06\x01"
+            assert dec.decode_value(tag, value) == ".1.3.6.1"
+            tag, value = dec.read()
+            assert (tag.nr, value) == (1, b"\x05")
+            assert dec.decode_value(tag, value) == 5
+            assert dec.read_integer() == 7
+            assert dec.eof()
+
+    def test_lazy_decode_value_options(self) -> None:
+        dec = asn1.Decoder(b"\x02\x02\x00\x01", lazy=True, strict=True)
+        tag, value = dec.read()
+        assert value == b"\x00\x01"
+        with pytest.raises(asn1.Asn1SyntaxError):
+            dec.decode_value(tag, value)
+
+    @pytest.mark.parametrize("buf", (b"\x40\x00", b"\x40\x03\x0a\x00\x00", b"\x40\x05\x0a\x00\x00\x01\x00"))
+    def test_error_ip_address_length(self, buf: bytes) -> None:
+        dec = asn1.Decoder(buf, ipv6_addresses=True)
+        with pytest.raises(asn1.Asn1SyntaxError):
+            dec.read()
+
+    def test_ip_address_ipv6(self) -> None:
+        buf = b"\x40\x10\x20\x01\x0d\xb8" + b"\x00" * 11 + b"\x01"
+        with pytest.raises(asn1.Asn1SyntaxError):
+            asn1.Decoder(buf).read()
+        tag, val = asn1.Decoder(buf, ipv6_addresses=True).read()
+        assert val == ipaddress.IPv6Address("2001:db8::1")
+        assert asn1.Decoder(memoryview(b"\x40\x04\x0a\x00\x00\x01")).read()[1] == ipaddress.IPv4Address("10.0.0.1")
+
+    def test_partial(self) -> None:
+        # SEQUENCE { INTEGER 1, IpAddress of 3 octets, INTEGER 2, NULL with content }
+        buf = b"\x30\x0e\x02\x01\x01\x40\x03\x0a\x00\x00\x02\x01\x02\x05\x01\x00"
+        with pytest.raises(asn1.Asn1SyntaxError):
+            asn1.Decoder(buf).read_all()
+        dec = asn1.Decoder(buf, partial=True)
+        (tag, values), = dec.read_all()
+        assert [value for _, value in values[::2]] == [1, 2]
+        assert values[1][0].nr | values[1][0].cls == asn1.Number.IPAddress
+        assert isinstance(values[1][1], asn1.Malformed)
+        assert values[1][1].data == b"\x0a\x00\x00"
+        assert isinstance(values[3][1], asn1.Malformed)
+        assert [error.tag for error in dec.errors] == [values[1][0], values[3][0]]
+        assert dec.errors[0] is values[1][1].error
+        assert dec.eof()
+
+    def test_partial_unreadable_length(self) -> None:
+        dec = asn1.Decoder(b"\x30\x07\x02\x01\x01\x04\x85\x01\x02", partial=True)
+        with dec.enter():
+            assert dec.read() == (asn1.Tag(asn1.Number.Integer, asn1.Type.Primitive, asn1.Class.Universal), 1)
+            tag, value = dec.read()
+            assert tag.nr == asn1.Number.OctetString
+            assert value.data == b"\x04\x85\x01\x02"
+            assert dec.eof()
+        assert len(dec.errors) == 1
+        assert isinstance(dec.errors[0], asn1.PrematureEndError)
+
+    def test_partial_unexpected_tag(self) -> None:
+        dec = asn1.Decoder(b"\x04\x01\x61\x02\x01\x05", partial=True)
+        assert isinstance(dec.read_integer(), asn1.Malformed)
+        assert dec.read_integer() == 5
+        assert isinstance(dec.errors[0], asn1.UnexpectedTagError)
+        dec.reset(b"\x02\x01\x05")
+        assert dec.errors == []
+
+    def test_read_struct(self) -> None:
+        class SecurityParameters(NamedTuple):
+            engine_id: bytes
+            boots: int
+            time: int
+            user: bytes
+
+        enc = asn1.Encoder()
+        enc.write_sequence([b"engine", 3, 1000, b"user"])
+        schema = (
+            asn1.Field("engine_id", asn1.Number.OctetString),
+            asn1.Field("boots", asn1.Number.Integer),
+            asn1.Field("time", asn1.Number.Integer),
+            asn1.Field("user", asn1.Number.OctetString),
+        )
+        assert asn1.Decoder(enc.output()).read_struct(schema, SecurityParameters) == SecurityParameters(
+            b"engine", 3, 1000, b"user"
+        )
+
+    def test_read_struct_optional_nested(self) -> None:
+        enc = asn1.Encoder()
+        with enc.enter(asn1.Number.Sequence):
+            enc.write(1)
+            with enc.enter(asn1.Number.Sequence):
+                enc.write("1.3.6.1", asn1.Number.ObjectIdentifier)
+            enc.write(b"explicit", asn1.Number.OctetString, cls=asn1.Class.Context)
+        schema = (
+            asn1.Field("version", asn1.Number.Integer),
+            asn1.Field("name", asn1.Number.OctetString, optional=True, default=b"public"),
+            asn1.Field("inner", asn1.Number.Sequence, schema=(asn1.Field("oid"),)),
+            asn1.Field("label", asn1.Tag.context(asn1.Number.OctetString), optional=True),
+            asn1.Field("missing", optional=True, default=0),
+        )
+        dec = asn1.Decoder(enc.output())
+        assert dec.read_struct(schema) == {
+            "version": 1,
+            "name": b"public",
+            "inner": {"oid": ".1.3.6.1"},
+            "label": b"explicit",
+            "missing": 0,
+        }
+        assert dec.eof()
+
+    def test_read_struct_errors(self) -> None:
+        enc = asn1.Encoder()
+        enc.write_sequence([1, b"x"])
+        data = enc.output()
+        with pytest.raises(asn1.UnexpectedTagError, match="Field 'name': Expected ObjectIdentifier"):
+            asn1.Decoder(data).read_struct([asn1.Field("version"), asn1.Field("name", asn1.Number.ObjectIdentifier)])
+        with pytest.raises(asn1.PrematureEndError, match="Field 'extra' is missing"):
+            asn1.Decoder(data).read_struct([asn1.Field("version"), asn1.Field("name"), asn1.Field("extra")])
+        with pytest.raises(asn1.DecodeError, match="after the last field"):
+            asn1.Decoder(data).read_struct([asn1.Field("version")])
+        with pytest.raises(asn1.UnexpectedTagError):
+            asn1.Decoder(data).read_struct([], expect=asn1.Tag.context(0, constructed=True))
+
+    @pytest.mark.parametrize(
+        ("buf", "value"),
+        (
+            (b"\x85\x02ab", asn1.TaggedValue(asn1.Class.Context, 5, b"ab")),
+            (b"\x4a\x01\x07", asn1.TaggedValue(asn1.Class.Application, 10, b"\x07")),
+            (b"\xdf\x81\x00\x00", asn1.TaggedValue(asn1.Class.Private, 128, b"")),
+            (b"\xa5\x03\x02\x01\x01", asn1.TaggedValue(asn1.Class.Context, 5, b"\x02\x01\x01", True)),
+        ),
+    )
+    def test_tagged_values(self, buf: bytes, value: asn1.TaggedValue) -> None:
+        dec = asn1.Decoder(buf, tagged_values=True)
+        assert dec.read()[1] == value
+        assert asn1.Decoder(buf).read()[1] == value.data
+
+        dec = asn1.Decoder(buf, tagged_values=True, lazy=True)
+        tag, data = dec.read()
+        assert dec.decode_value(tag, data) == value
+
+        enc = asn1.Encoder()
+        enc.write(value)
+        assert enc.output() == buf
+
+    def test_tagged_values_known_numbers(self) -> None:
+        dec = asn1.Decoder(b"\x41\x01\x07\x80\x00\x04\x01a", tagged_values=True)
+        assert dec.read()[1] == 7
+        assert dec.read()[1] is None
+        assert dec.read()[1] == b"a"
+
+    def test_tagged_values_roundtrip(self) -> None:
+        enc = asn1.Encoder()
+        with enc.enter(asn1.Number.Sequence):
+            enc.write(asn1.TaggedValue(asn1.Class.Context, 9, b"vendor"), asn1.Number.OctetString)
+            enc.write(1)
+        data = enc.output()
+        dec = asn1.Decoder(data, tagged_values=True)
+        with dec.enter():
+            tag, value = dec.read()
+        assert tag == asn1.Tag.context(9)
+        sequence = asn1.Tag(asn1.Number.Sequence, asn1.Type.Constructed)
+        assert asn1.encode([(sequence, [(tag, value), (asn1.Tag(asn1.Number.Integer), 1)])]) == data
+
+    def test_window(self) -> None:
+        ring = bytearray(b"\xff\xff\x02\x01\x07\x04\x02ab\xff")
+        dec = asn1.Decoder(ring, start=2, length=7)
+        assert dec.read() == (asn1.Tag(asn1.Number.Integer), 7)
+        tag, value = dec.read()
+        assert value == b"ab" and type(value) is bytes
+        assert dec.eof()
+
+        # the window is not copied, the decoder sees writes into the buffer
+        ring[4] = 0x08
+        dec.reset(ring, 2, 3)
+        assert dec.read_integer() == 8
+        assert dec.eof()
+        dec.reset(ring, start=5, length=3)
+        with pytest.raises(asn1.PrematureEndError) as exc_info:
+            dec.read()
+        assert exc_info.value.offset == 2
+
+    @pytest.mark.parametrize(("start", "length"), ((-1, None), (4, 2), (2, 3)))
+    def test_window_error(self, start: int, length: Any) -> None:
+        with pytest.raises(ValueError):
+            asn1.Decoder(b"\x05\x00\x05", start=start, length=length)
+
+    @pytest.mark.parametrize("number", (asn1.Number.OctetString, asn1.Number.Opaque))
+    def test_view_threshold(self, number: asn1.Number) -> None:
+        blob = bytes(range(256)) * 4
+        enc = asn1.Encoder()
+        enc.write(b"small", number)
+        enc.write(blob, number)
+        dec = asn1.Decoder(enc.output(), view_threshold=len(blob))
+        assert dec.read()[1] == b"small"
+        tag, value = dec.read()
+        assert tag.nr | tag.cls == number
+        assert isinstance(value, asn1.OctetView)
+        assert len(value) == len(blob)
+        assert value.read(1000) == blob[:1000]
+        assert value.read(1000) == blob[1000:]
+        assert value.read(10) == b""
+        assert value.to_bytes() == blob
+        assert dec.eof()
+
+    def test_view_threshold_options(self) -> None:
+        enc = asn1.Encoder()
+        enc.write(b"text\x00\x00", asn1.Number.OctetString)
+        data = enc.output()
+        value = asn1.Decoder(data, view_threshold=4, hex_strings=True, strip_padding=True).read()[1]
+        assert isinstance(value, asn1.OctetView)
+        assert value.read() == b"text\x00\x00"
+        assert asn1.Decoder(data, view_threshold=4).read_octet_string() == b"text\x00\x00"
+
+        dec = asn1.Decoder(data, view_threshold=4, lazy=True)
+        tag, raw = dec.read()
+        assert isinstance(dec.decode_value(tag, raw), asn1.OctetView)
+
+    def test_view_threshold_truncated(self) -> None:
+        dec = asn1.Decoder(b"\x04\x82\x01\x00abc", view_threshold=16)
+        with pytest.raises(asn1.PrematureEndError):
+            dec.read()
+
+    @pytest.mark.parametrize("wrap", (bytearray, memoryview, lambda data: memoryview(bytearray(data))))
+    def test_enter_buffer_input(self, wrap: Any) -> None:
+        data = b"\x30\x0f\x04\x02ab\x30\x09\x06\x03\x2b\x06\x01\x30\x02\x04\x00\x04\x01c"
+        dec = asn1.Decoder(wrap(data))
+        with dec.enter():
+            assert dec.read() == (asn1.Tag(asn1.Number.OctetString), b"ab")
+            with dec.enter():
+                assert dec.read()[1] == ".1.3.6.1"
+                with dec.enter():
+                    tag, value = dec.read()
+                    assert value == b"" and type(value) is bytes
+                assert dec.eof()
+        tag, value = dec.read()
+        assert value == b"c" and type(value) is bytes
+        assert dec.eof()
+
+    @pytest.mark.parametrize("wrap", (bytes, bytearray, memoryview))
+    @pytest.mark.parametrize(
+        ("data", "error", "offset"),
+        (
+            (b"\x30\x0a\x04\x02ab\x30\x04\x02\x00\x05\x00", asn1.Asn1SyntaxError, 8),
+            (b"\x30\x07\x05\x00\x30\x03\x04\x05a", asn1.PrematureEndError, 8),
+        ),
+    )
+    def test_enter_buffer_input_error(self, wrap: Any, data: bytes, error: Any, offset: int) -> None:
+        dec = asn1.Decoder(wrap(data))
+        with pytest.raises(error) as exc_info:
+            with dec.enter():
+                dec.read()
+                with dec.enter():
+                    dec.read()
+        assert exc_info.value.offset == offset
+        assert exc_info.value.depth == 2
+
+    @pytest.mark.parametrize(
+        ("data", "value"),
+        [
+            (b"\x04\x04ab\x00\x00", b"ab\x00\x00"),
+            (b"\x04\x02\x00\x01", b"\x00\x01"),
+        ],
+    )
+    def test_read_octet_string_raw(self, data: bytes, value: bytes) -> None:
+        dec = asn1.Decoder(data, hex_strings=True, strip_padding=True)
+        assert dec.read_octet_string() == value
+        assert dec.eof()
+
+    @pytest.mark.parametrize(
+        "buf",
+        (
+            b"\x30\x07\x02\x01\x01\x02\x02\x00\x01",
+            b"\x30\x06\x02\x01\x01\x01\x01\x01",
+            b"\x30\x06\x02\x01\x01\x04\x81\x00",
+        ),
+    )
+    def test_strict_error_offset(self, buf: bytes) -> None:
+        dec = asn1.Decoder(buf, strict=True)
+        with dec.enter():
+            dec.read()
+            with pytest.raises(asn1.Asn1SyntaxError) as exc_info:
+                dec.read()
+        assert exc_info.value.offset == 5
+        assert exc_info.value.depth == 1
+
 
 class TestEncoderDecoder:
     @pytest.mark.parametrize(
@@ -596,6 +1556,9 @@ class TestEncoderDecoder:
             667441897913742713771034596334288035,
             664674827807729028941298133900846368,
             666811959353093594446621165172641478,
+            2 ** 127,
+            2 ** 128,
+            2 ** 4096 + 1,
         ),
     )
     def test_big_numbers(self, value: int) -> None:
@@ -613,6 +1576,9 @@ class TestEncoderDecoder:
             -667441897913742713771034596334288035,
             -664674827807729028941298133900846368,
             -666811959353093594446621165172641478,
+            -(2 ** 127) - 1,
+            -(2 ** 128),
+            -(2 ** 4096) - 1,
         ),
     )
     def test_big_negative_numbers(self, value: int) -> None:
@@ -622,3 +1588,278 @@ class TestEncoderDecoder:
         decoder = asn1.Decoder(encoded_bytes)
         tag, val = decoder.read()
         assert val == value
+
+
+class TestStreamDecoder:
+    def test_feed(self) -> None:
+        stream = asn1.StreamDecoder()
+        assert stream.feed(b"\x30\x06\x02\x01") == []
+        assert stream.pending == 4
+        assert stream.feed(b"\x01\x02\x01\x02\x04\x82\x01") == [b"\x30\x06\x02\x01\x01\x02\x01\x02"]
+        assert stream.pending == 3
+        long_string = b"\x04\x82\x01\x00" + b"x" * 0x100
+        assert stream.feed(long_string[3:] + b"\x05\x00\x02") == [long_string, b"\x05\x00"]
+        assert stream.pending == 1
+        assert stream.feed(b"\x01\x07") == [b"\x02\x01\x07"]
+        assert stream.pending == 0
+
+    def test_feed_decode(self) -> None:
+        stream = asn1.StreamDecoder()
+        messages = stream.feed(b"\x3f\x83\x01\x03foo")
+        assert len(messages) == 1
+        tag, val = asn1.Decoder(messages[0]).read()
+        assert tag.nr == 0x181
+
+    @pytest.mark.parametrize("buf", (b"\x30\x80\x02\x01\x01\x00\x00", b"\x04\xff"))
+    def test_error_feed(self, buf: bytes) -> None:
+        stream = asn1.StreamDecoder()
+        with pytest.raises(asn1.Error):
+            stream.feed(buf)
+
+
+    def test_error_feed_recovers(self) -> None:
+        stream = asn1.StreamDecoder()
+        with pytest.raises(asn1.Error):
+            stream.feed(b"\x04\xff\x02")
+        assert stream.pending == 0
+        assert stream.feed(b"\x02\x01\x07") == [b"\x02\x01\x07"]
+
+    def test_error_feed_keeps_complete(self) -> None:
+        # tags completed before the malformed one are returned by the next call
+        stream = asn1.StreamDecoder()
+        with pytest.raises(asn1.Error):
+            stream.feed(b"\x02\x01\x05\x05\x00\x30\x80")
+        assert stream.pending == 5
+        assert stream.feed(b"") == [b"\x02\x01\x05", b"\x05\x00"]
+        assert stream.feed(b"\x02\x01\x07") == [b"\x02\x01\x07"]
+
+    def test_max_message_size(self) -> None:
+        stream = asn1.StreamDecoder(max_message_size=8)
+        assert stream.feed(b"\x04\x06abcdef\x30") == [b"\x04\x06abcdef"]
+        # a declared length is rejected before its value is received
+        with pytest.raises(asn1.LimitError) as exc_info:
+            stream.feed(b"\x84\x7f\xff\xff\xff")
+        assert exc_info.value.offset == 0
+        assert stream.pending == 0
+        assert stream.feed(b"\x05\x00") == [b"\x05\x00"]
+
+
+@pytest.mark.parametrize(
+    ("buf", "result"),
+    (
+        (b"", []),
+        (b"\x30", []),
+        (b"\x02\x01\x01\x30\x03\x02\x01\x02", [(0, 3), (3, 8)]),
+        (b"\x02\x01\x01\x04\x81\x02ab\x05", [(0, 3), (3, 8)]),
+        (memoryview(b"\x05\x00\x05\x00"), [(0, 2), (2, 4)]),
+    ),
+)
+def test_message_boundaries(buf: bytes, result: list) -> None:
+    assert asn1.message_boundaries(buf) == result
+
+
+def test_split_messages() -> None:
+    messages = asn1.split_messages(b"\x30\x03\x02\x01\x01\x30\x03\x02\x01\x02")
+    assert messages == [b"\x30\x03\x02\x01\x01", b"\x30\x03\x02\x01\x02"]
+    assert [asn1.Decoder(message).read_all()[0][1][0][1] for message in messages] == [1, 2]
+    assert asn1.split_messages(b"") == []
+
+
+@pytest.mark.parametrize("buf", (b"\x30\x03\x02\x01\x01\x30", b"\x02\x02\x01"))
+def test_error_split_messages(buf: bytes) -> None:
+    with pytest.raises(asn1.Error):
+        asn1.split_messages(buf)
+
+
+@pytest.mark.parametrize(
+    "exc_type", (asn1.Error, asn1.Asn1SyntaxError, asn1.PrematureEndError, asn1.MessageTooBigError)
+)
+def test_error_pickle(exc_type: type) -> None:
+    error = exc_type("Bad value.", offset=3, tag=asn1.Tag(asn1.Number.Integer), depth=1)
+    clone = pickle.loads(pickle.dumps(error))
+    assert type(clone) is exc_type
+    assert (clone.args, clone.offset, clone.tag, clone.depth) == (error.args, 3, error.tag, 1)
+    assert str(clone) == str(error)
+    assert pickle.loads(pickle.dumps(exc_type())).offset is None
+
+
+class TestTag:
+    @pytest.mark.parametrize(
+        ("buf", "result"),
+        (
+            (b"\x02\x01\x01", "Tag(nr=Integer, typ=Primitive, cls=Universal)"),
+            (b"\x30\x00", "Tag(nr=Sequence, typ=Constructed, cls=Universal)"),
+            (b"\x43\x01\x01", "Tag(nr=TimeTicks, typ=Primitive, cls=Application)"),
+            (b"\x1e\x02\x00a", "Tag(nr=BMPString, typ=Primitive, cls=Universal)"),
+            (b"\xa2\x00", "Tag(nr=0x2, typ=Constructed, cls=Context)"),
+            (b"\xdf\x83\x01\x00", "Tag(nr=0x181, typ=Primitive, cls=Private)"),
+        ),
+    )
+    def test_repr(self, buf: bytes, result: str) -> None:
+        assert repr(asn1.Decoder(buf).peek()) == result
+
+    def test_bmp_string_alias(self) -> None:
+        assert asn1.Number(0x1E) is asn1.Number.BMPString
+        assert asn1.Number.UnicodeString is asn1.Number.BMPString
+        assert asn1.Number(0x1E).name == "BMPString"
+
+    def test_tuple(self) -> None:
+        tag = asn1.Decoder(b"\x02\x01\x01").peek()
+        nr, typ, cls = tag
+        assert (nr, typ, cls) == (asn1.Number.Integer, asn1.Type.Primitive, asn1.Class.Universal)
+        assert tag[0] == asn1.Number.Integer
+        assert tag == asn1.Tag(asn1.Number.Integer, asn1.Type.Primitive, asn1.Class.Universal)
+        assert len({tag, asn1.Tag(2, 0, 0)}) == 1
+
+    @pytest.mark.parametrize(
+        ("tag", "tag_byte", "constructed", "class_name"),
+        (
+            (asn1.Tag.universal(asn1.Number.Integer), 0x02, False, "Universal"),
+            (asn1.Tag.universal(asn1.Number.Sequence, constructed=True), 0x30, True, "Universal"),
+            (asn1.Tag.application(3), 0x43, False, "Application"),
+            (asn1.Tag.context(2, constructed=True), 0xA2, True, "Context"),
+            (asn1.Tag.private(0x181), 0xDF, False, "Private"),
+            (asn1.Tag(asn1.Number.Counter32), 0x41, False, "Universal"),
+            (asn1.Tag.application(asn1.Number.Counter32), 0x41, False, "Application"),
+            (asn1.Tag.context(asn1.Number.NoSuchObject), 0x80, False, "Context"),
+        ),
+    )
+    def test_properties(self, tag: asn1.Tag, tag_byte: int, constructed: bool, class_name: str) -> None:
+        assert tag.tag_byte == tag_byte
+        assert tag.is_constructed is constructed
+        assert tag.class_name == class_name
+        assert tag.type_name == ("Constructed" if constructed else "Primitive")
+
+    @pytest.mark.parametrize(
+        ("value", "nr", "cls", "result"),
+        (
+            (5, asn1.Number.Counter32, asn1.Class.Application, b"\x41\x01\x05"),
+            (5, asn1.Number.Counter32, None, b"\x41\x01\x05"),
+            (5, 0x41, None, b"\x41\x01\x05"),
+            (None, asn1.Number.NoSuchObject, asn1.Class.Context, b"\x80\x00"),
+            (None, asn1.Number.EndOfMibView, asn1.Class.Context, b"\x82\x00"),
+        ),
+    )
+    def test_class_carrying_number(self, value: Any, nr: asn1.Number, cls: Any, result: bytes) -> None:
+        # the members of SNMP types include their class bits, they are written as one identifier octet
+        enc = asn1.Encoder()
+        enc.write(value, nr, cls=cls)
+        assert enc.output() == result
+
+    @pytest.mark.parametrize("nr", (0x1F, 0x7F, 0x80, 0x181, 0x4000))
+    def test_high_tag_number_roundtrip(self, nr: int) -> None:
+        enc = asn1.Encoder()
+        with enc.enter(nr, asn1.Class.Context):
+            enc.write(1)
+        dec = asn1.Decoder(enc.output())
+        assert dec.peek() == asn1.Tag.context(nr, constructed=True)
+        assert dec.peek().tag_byte == 0xBF
+        with dec.enter():
+            tag, val = dec.read()
+            assert val == 1
+
+    def test_defaults(self) -> None:
+        assert asn1.Tag(asn1.Number.Integer) == (asn1.Number.Integer, asn1.Type.Primitive, asn1.Class.Universal)
+        assert asn1.Tag(nr=2, cls=asn1.Class.Context) == asn1.Tag.context(2)
+        assert asn1.Tag(5).number == 5
+
+    @pytest.mark.skipif(sys.version_info < (3, 10), reason="structural pattern matching requires python 3.10")
+    def test_match_args(self) -> None:
+        assert asn1.Tag.__match_args__ == ("nr", "typ", "cls")
+
+
+def test_public_names() -> None:
+    for name in asn1.__all__:
+        assert hasattr(asn1, name)
+    assert asn1.Number.Sequence | asn1.Type.Constructed == 0x30
+    assert asn1.Number.Counter32 == 0x41
+    assert asn1.Number.Opaque == 0x44
+    assert asn1.Number.Counter64 == 0x46
+
+
+@pytest.mark.parametrize(
+    "data",
+    (
+        b"\x30\x0b\x02\x01\x01\x04\x02ab\x06\x02\x2b\x06",
+        b"\x30\x12\x40\x04\x7f\x00\x00\x01\x41\x01\x05\xa2\x07\x80\x00\x01\x01\xff\x05\x00",
+        b"\x5f\x28\x01\x00\x13\x02hi",
+    ),
+)
+def test_encode_decode_tree(data: bytes) -> None:
+    assert asn1.encode(asn1.decode(data)) == data
+
+
+@pytest.mark.parametrize(
+    "data",
+    (
+        b"\x13\x05Hello",
+        b"\x16\x04a@b.",
+        b"\x0c\x03\xc3\xa9t",
+        b"\x12\x03123",
+        b"\x1a\x02hi",
+        b"\x17\x0d991231235959Z",
+        b"\x1e\x04\x00c\x04\x10",
+    ),
+)
+def test_encode_decode_tree_strings(data: bytes) -> None:
+    assert asn1.encode(asn1.decode(data)) == data
+    assert asn1.verify_roundtrip(data) is None
+
+
+def test_encode_decode_tree_ipv6_and_view() -> None:
+    data = b"\x30\x1a\x40\x10" + bytes(range(16)) + b"\x04\x06" + b"x" * 6
+    tree = asn1.Decoder(data, ipv6_addresses=True, view_threshold=4).read_all()
+    assert isinstance(tree[0][1][1][1], asn1.OctetView)
+    assert asn1.encode(tree) == data
+
+
+def test_decode_tree() -> None:
+    tree = asn1.decode(b"\x30\x07\x02\x01\x05\x30\x02\x05\x00")
+    assert tree == [
+        (
+            asn1.Tag(asn1.Number.Sequence, asn1.Type.Constructed),
+            [
+                (asn1.Tag(asn1.Number.Integer), 5),
+                (asn1.Tag(asn1.Number.Sequence, asn1.Type.Constructed), [(asn1.Tag(asn1.Number.Null), None)]),
+            ],
+        )
+    ]
+
+
+def test_dump() -> None:
+    data = b"\x30\x0f\x41\x01\x05\x04\x02\x00\x01\xa1\x06\x06\x01\x2b\x04\x01a"
+    assert asn1.dump(data).splitlines() == [
+        "    0:d=0  hl=2 l=  15 cons: Sequence",
+        "    2:d=1  hl=2 l=   1 prim:  Counter32          :5",
+        "    5:d=1  hl=2 l=   2 prim:  OctetString        :0001",
+        "    9:d=1  hl=2 l=   6 cons:  Context [ 1 ]",
+        "   11:d=2  hl=2 l=   1 prim:   ObjectIdentifier  :.1.3",
+        "   14:d=2  hl=2 l=   1 prim:   OctetString       :a",
+    ]
+
+
+def test_dump_invalid_value() -> None:
+    assert asn1.dump(b"\x02\x00") == "    0:d=0  hl=2 l=   0 prim: Integer             :<invalid> 0200"
+
+
+def test_verify_roundtrip() -> None:
+    assert asn1.verify_roundtrip(b"\x30\x06\x02\x01\x01\x02\x01\x02") is None
+    # non-minimal integer
+    assert asn1.verify_roundtrip(b"\x30\x07\x02\x01\x01\x02\x02\x00\x02") == asn1.Mismatch(
+        offset=1,
+        expected=b"\x07\x02\x01\x01\x02\x02\x00\x02",
+        actual=b"\x06\x02\x01\x01\x02\x01\x02",
+        context="    0:d=0  hl=2 l=   7 cons: Sequence",
+    )
+    # long form length
+    mismatch = asn1.verify_roundtrip(b"\x04\x81\x01a")
+    assert mismatch is not None
+    assert mismatch.offset == 1
+    assert mismatch.context == "    0:d=0  hl=3 l=   1 prim: OctetString         :a"
+
+
+def test_verify_roundtrip_custom_codec() -> None:
+    mismatch = asn1.verify_roundtrip(b"\x02\x01\x05", lambda data: 6, lambda value: asn1.encode([(asn1.Tag(2), value)]))
+    assert mismatch is not None
+    assert (mismatch.offset, mismatch.expected, mismatch.actual) == (2, b"\x05", b"\x06")
+    assert mismatch.context.endswith(":5")
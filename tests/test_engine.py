@@ -0,0 +1,115 @@
+import ipaddress
+from typing import Any
+
+import pytest
+
+from aiosnmp.engine import EngineIdFormat, EngineTimeCache, generate_engine_id, parse_engine_id
+from aiosnmp.exceptions import SnmpInvalidEngineId
+
+
+@pytest.mark.parametrize(
+    ("value", "fmt", "expected", "parsed"),
+    (
+        (
+            ipaddress.IPv4Address("10.0.0.1"),
+            None,
+            b"\x80\x00\x1f\x88\x01\x0a\x00\x00\x01",
+            ipaddress.IPv4Address("10.0.0.1"),
+        ),
+        (
+            ipaddress.IPv6Address("::1"),
+            None,
+            b"\x80\x00\x1f\x88\x02" + b"\x00" * 15 + b"\x01",
+            ipaddress.IPv6Address("::1"),
+        ),
+        ("00:11:22:aa:bb:cc", EngineIdFormat.MAC, b"\x80\x00\x1f\x88\x03\x00\x11\x22\xaa\xbb\xcc", "00:11:22:aa:bb:cc"),
+        ("router1", None, b"\x80\x00\x1f\x88\x04router1", "router1"),
+        (b"\x01\x02", None, b"\x80\x00\x1f\x88\x05\x01\x02", b"\x01\x02"),
+        (b"\xde\xad", 200, b"\x80\x00\x1f\x88\xc8\xde\xad", b"\xde\xad"),
+    ),
+)
+def test_engine_id_roundtrip(value: Any, fmt: Any, expected: bytes, parsed: Any) -> None:
+    engine_id = generate_engine_id(value, enterprise=8072, fmt=fmt)
+    assert engine_id == expected
+
+    info = parse_engine_id(engine_id)
+    assert info.enterprise == 8072
+    assert info.value == parsed
+    assert info.raw == engine_id
+
+
+def test_parse_legacy_engine_id() -> None:
+    info = parse_engine_id(b"\x00\x00\x00\x09" + b"\x01" * 8)
+    assert info.enterprise == 9
+    assert info.format is None
+    assert info.format_name == "legacy"
+    assert info.value == b"\x01" * 8
+
+
+def test_engine_id_str() -> None:
+    info = parse_engine_id(generate_engine_id("router1", enterprise=8072))
+    assert str(info) == "8072:Text:router1"
+
+
+@pytest.mark.parametrize(
+    "engine_id",
+    (b"\x80\x00", b"\x80" * 33, b"\x80\x00\x1f\x88\x01\x0a\x00", b"\x80\x00\x1f\x88\x03\x00\x11"),
+)
+def test_parse_invalid_engine_id(engine_id: bytes) -> None:
+    with pytest.raises(SnmpInvalidEngineId):
+        parse_engine_id(engine_id)
+
+
+@pytest.mark.parametrize(
+    ("value", "fmt"),
+    (("x" * 28, None), (b"", None), ("00:11", EngineIdFormat.MAC), (b"\x01", 6), (1.1, None)),
+)
+def test_generate_invalid_engine_id(value: Any, fmt: Any) -> None:
+    with pytest.raises(SnmpInvalidEngineId):
+        generate_engine_id(value, enterprise=8072, fmt=fmt)
+
+
+class FakeClock:
+    def __init__(self) -> None:
+        self.now = 1000.0
+
+    def __call__(self) -> float:
+        return self.now
+
+
+def test_engine_time_cache_estimate() -> None:
+    clock = FakeClock()
+    cache = EngineTimeCache(clock=clock)
+    assert cache.get(b"engine") is None
+    assert cache.update(b"engine", 3, 500)
+    assert b"engine" in cache
+    clock.now += 42.5
+    assert cache.get(b"engine") == (3, 542)
+
+
+def test_engine_time_cache_ignores_older_values() -> None:
+    cache = EngineTimeCache(clock=FakeClock())
+    assert cache.update(b"engine", 3, 500)
+    assert not cache.update(b"engine", 3, 400)
+    assert not cache.update(b"engine", 2, 9000)
+    assert cache.get(b"engine") == (3, 500)
+    assert cache.update(b"engine", 4, 1)
+    assert cache.get(b"engine") == (4, 1)
+
+
+@pytest.mark.parametrize(
+    ("boots", "engine_time", "result"),
+    ((3, 500, True), (3, 351, True), (3, 349, False), (2, 500, False), (4, 0, True)),
+)
+def test_engine_time_cache_window(boots: int, engine_time: int, result: bool) -> None:
+    cache = EngineTimeCache(clock=FakeClock())
+    cache.update(b"engine", 3, 500)
+    assert cache.is_in_time_window(b"engine", boots, engine_time) is result
+
+
+def test_engine_time_cache_unknown_engine() -> None:
+    cache = EngineTimeCache(clock=FakeClock())
+    assert not cache.is_in_time_window(b"engine", 1, 1)
+    cache.update(b"engine", 1, 1)
+    cache.remove(b"engine")
+    assert len(cache) == 0
@@ -0,0 +1,664 @@
+import ipaddress
+import json
+from concurrent.futures import ThreadPoolExecutor
+from typing import List, Union
+
+import pytest
+
+from aiosnmp.asn1 import Class, Encoder, Error, Malformed, MessageTooBigError, Number, OctetView
+from aiosnmp.exceptions import SnmpErrorNoSuchName, SnmpErrorNotWritable, SnmpErrorStatus
+from aiosnmp.message import (
+    ErrorStatus,
+    GetBulkRequest,
+    GetNextRequest,
+    GetResponse,
+    GetRequest,
+    InformRequest,
+    MismatchKind,
+    PDUType,
+    RequestIdGenerator,
+    Response,
+    SnmpMessage,
+    SnmpResponse,
+    SnmpV1TrapMessage,
+    SnmpV1TrapPDU,
+    SnmpV2Trap,
+    SnmpV2TrapMessage,
+    SnmpVarbind,
+    SnmpVersion,
+    VarbindMismatch,
+    correlate_varbinds,
+    decode_many,
+    decode_many_parallel,
+    decode_response,
+    encode_get,
+    encode_getbulk,
+    encode_inform_response,
+    error_status_exception,
+    first_outside,
+    parse_message,
+    parse_trap,
+    peek_pdu_type,
+    peek_request_id,
+    to_dict,
+)
+
+
+def _v1_trap() -> bytes:
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(SnmpVersion.v1, Number.Integer)
+        encoder.write("public", Number.OctetString)
+        with encoder.enter(PDUType.SNMPv1Trap, Class.Context):
+            encoder.write("1.3.6.1.4.1.8072.4", Number.ObjectIdentifier)
+            encoder.write(ipaddress.IPv4Address("10.0.0.1"))
+            encoder.write(6, Number.Integer)
+            encoder.write(17, Number.Integer)
+            encoder.write(1234, Number.Integer)
+            with encoder.enter(Number.Sequence):
+                with encoder.enter(Number.Sequence):
+                    encoder.write("1.3.6.1.2.1.1.5.0", Number.ObjectIdentifier)
+                    encoder.write("host")
+    return encoder.output()
+
+
+def _v2_notification(pdu_type: PDUType) -> bytes:
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(SnmpVersion.v2c, Number.Integer)
+        encoder.write("public", Number.OctetString)
+        with encoder.enter(pdu_type, Class.Context):
+            encoder.write(42, Number.Integer)
+            encoder.write(0, Number.Integer)
+            encoder.write(0, Number.Integer)
+            with encoder.enter(Number.Sequence):
+                with encoder.enter(Number.Sequence):
+                    encoder.write("1.3.6.1.6.3.1.1.4.1.0", Number.ObjectIdentifier)
+                    encoder.write("1.3.6.1.6.3.1.1.5.1", Number.ObjectIdentifier)
+    return encoder.output()
+
+
+def test_parse_v1_trap() -> None:
+    message = parse_trap(_v1_trap())
+    assert isinstance(message, SnmpV1TrapMessage)
+    assert message.version == SnmpVersion.v1
+    assert message.community == "public"
+    assert message.data.enterprise == ".1.3.6.1.4.1.8072.4"
+    assert message.data.agent_addr == ipaddress.IPv4Address("10.0.0.1")
+    assert message.data.generic_trap == 6
+    assert message.data.specific_trap == 17
+    assert message.data.time_stamp == 1234
+    assert len(message.data.varbinds) == 1
+    assert message.data.varbinds[0].oid == ".1.3.6.1.2.1.1.5.0"
+    assert message.data.varbinds[0].value == b"host"
+
+
+@pytest.mark.parametrize(
+    ("pdu_type", "pdu_cls"),
+    ((PDUType.SNMPv2Trap, SnmpV2Trap), (PDUType.InformRequest, InformRequest)),
+)
+def test_parse_v2_notification(pdu_type: PDUType, pdu_cls: type) -> None:
+    message = parse_trap(_v2_notification(pdu_type))
+    assert isinstance(message, SnmpV2TrapMessage)
+    assert type(message.data) is pdu_cls
+    assert message.data.request_id == 42
+    assert message.data.varbinds[0].value == ".1.3.6.1.6.3.1.1.5.1"
+
+
+def test_v2_trap_message_decode_skips_inform() -> None:
+    assert SnmpV2TrapMessage.decode(_v2_notification(PDUType.SNMPv2Trap)) is not None
+    assert SnmpV2TrapMessage.decode(_v2_notification(PDUType.InformRequest)) is None
+    assert SnmpV2TrapMessage.decode(_v1_trap()) is None
+    assert SnmpV1TrapMessage.decode(_v1_trap()) is not None
+
+
+def test_parse_trap_not_a_notification() -> None:
+    assert parse_trap(_v2_notification(PDUType.GetResponse)) is None
+
+
+def test_parse_trap_on_varbind() -> None:
+    varbinds: List[SnmpVarbind] = []
+    message = parse_trap(_v1_trap(), on_varbind=varbinds.append)
+    assert isinstance(message, SnmpV1TrapMessage)
+    assert message.data.varbinds == []
+    assert [(varbind.oid, varbind.value) for varbind in varbinds] == [(".1.3.6.1.2.1.1.5.0", b"host")]
+
+    varbinds.clear()
+    message = parse_trap(_v2_notification(PDUType.SNMPv2Trap), on_varbind=varbinds.append)
+    assert message is not None and message.data.varbinds == []
+    assert [varbind.value for varbind in varbinds] == [".1.3.6.1.6.3.1.1.5.1"]
+
+
+def test_parse_message_on_varbind() -> None:
+    oids = [".1.3.6.1.2.1.1.5.0", ".1.3.6.1.2.1.1.3.0"]
+    seen: List[str] = []
+    message = parse_message(encode_get("public", 1, oids), on_varbind=lambda varbind: seen.append(varbind.oid))
+    assert message.data.varbinds == []
+    assert seen == oids
+
+
+def test_parse_message_on_varbind_raises() -> None:
+    # an exception of the callback stops decoding the remaining varbinds
+    seen: List[str] = []
+
+    def on_varbind(varbind: SnmpVarbind) -> None:
+        seen.append(varbind.oid)
+        raise RuntimeError("queue full")
+
+    with pytest.raises(RuntimeError):
+        parse_message(encode_get("public", 1, [".1.3.6.1.2.1.1.5.0", ".1.3.6.1.2.1.1.3.0"]), on_varbind=on_varbind)
+    assert seen == [".1.3.6.1.2.1.1.5.0"]
+
+
+def test_encode_inform_response() -> None:
+    inform = _v2_notification(PDUType.InformRequest)
+    message = parse_trap(inform)
+    assert isinstance(message, SnmpV2TrapMessage)
+    # only the PDU tag differs from the inform
+    assert encode_inform_response(message) == inform.replace(b"\xa6", b"\xa2", 1)
+
+    response = decode_response(encode_inform_response(message))
+    assert response.request_id == 42
+    assert response.error_status == 0
+    assert [varbind.value for varbind in response.varbinds] == [".1.3.6.1.6.3.1.1.5.1"]
+
+
+def test_encode_inform_response_not_an_inform() -> None:
+    message = parse_trap(_v2_notification(PDUType.SNMPv2Trap))
+    assert isinstance(message, SnmpV2TrapMessage)
+    with pytest.raises(ValueError):
+        encode_inform_response(message)
+
+
+def test_varbind_encode_keeps_type() -> None:
+    encoder = Encoder()
+    SnmpVarbind(".1.3.6.1", 7, Number.Counter32).encode(encoder)
+    SnmpVarbind(".1.3.6.1", None, Number.NoSuchInstance).encode(encoder)
+    SnmpVarbind(".1.3.6.1", b"\x9f\x78\x04\x3f\x80\x00\x00", Number.Opaque).encode(encoder)
+    SnmpVarbind(".1.3.6.1", 7).encode(encoder)
+    assert encoder.output() == (
+        b"\x30\x08\x06\x03\x2b\x06\x01\x41\x01\x07"
+        b"\x30\x07\x06\x03\x2b\x06\x01\x81\x00"
+        b"\x30\x0e\x06\x03\x2b\x06\x01\x44\x07\x9f\x78\x04\x3f\x80\x00\x00"
+        b"\x30\x08\x06\x03\x2b\x06\x01\x02\x01\x07"
+    )
+
+
+def test_v1_trap_encode() -> None:
+    varbinds = [SnmpVarbind(".1.3.6.1.2.1.1.5.0", b"host")]
+    pdu = SnmpV1TrapPDU(".1.3.6.1.4.1.8072.4", ipaddress.IPv4Address("10.0.0.1"), 6, 17, 1234, varbinds)
+    data = SnmpV1TrapMessage(SnmpVersion.v1, "public", pdu).encode()
+    assert data == _v1_trap().replace(b"\x02\x02\x04\xd2", b"\x43\x02\x04\xd2", 1)
+
+    message = parse_trap(data)
+    assert isinstance(message, SnmpV1TrapMessage)
+    assert message.data.enterprise == ".1.3.6.1.4.1.8072.4"
+    assert message.data.agent_addr == ipaddress.IPv4Address("10.0.0.1")
+    assert message.data.time_stamp == 1234
+    assert message.encode() == data
+
+
+@pytest.mark.parametrize(
+    ("generic_trap", "specific_trap", "trap_oid"),
+    (
+        (0, 0, ".1.3.6.1.6.3.1.1.5.1"),
+        (2, 0, ".1.3.6.1.6.3.1.1.5.3"),
+        (5, 0, ".1.3.6.1.6.3.1.1.5.6"),
+        (6, 17, ".1.3.6.1.4.1.8072.4.0.17"),
+    ),
+)
+def test_v1_trap_to_v2(generic_trap: int, specific_trap: int, trap_oid: str) -> None:
+    varbinds = [SnmpVarbind(".1.3.6.1.2.1.1.5.0", b"host")]
+    pdu = SnmpV1TrapPDU(
+        ".1.3.6.1.4.1.8072.4", ipaddress.IPv4Address("10.0.0.1"), generic_trap, specific_trap, 1234, varbinds
+    )
+    assert pdu.trap_oid == trap_oid
+
+    message = SnmpV1TrapMessage(SnmpVersion.v1, "public", pdu).to_v2()
+    assert message.version == SnmpVersion.v2c
+    assert isinstance(message.data, SnmpV2Trap)
+    assert [(varbind.oid, varbind.value, varbind.number) for varbind in message.data.varbinds] == [
+        (".1.3.6.1.2.1.1.3.0", 1234, Number.TimeTicks),
+        (".1.3.6.1.6.3.1.1.4.1.0", trap_oid, Number.ObjectIdentifier),
+        (".1.3.6.1.2.1.1.5.0", b"host", None),
+        (".1.3.6.1.6.3.18.1.3.0", ipaddress.IPv4Address("10.0.0.1"), Number.IPAddress),
+        (".1.3.6.1.6.3.1.1.4.3.0", ".1.3.6.1.4.1.8072.4", Number.ObjectIdentifier),
+    ]
+
+    # the normalized trap decodes like one sent by a v2c agent
+    decoded = parse_trap(SnmpMessage(message.version, message.community, message.data).encode())
+    assert isinstance(decoded, SnmpV2TrapMessage)
+    assert [varbind.value for varbind in decoded.data.varbinds] == [
+        1234,
+        trap_oid,
+        b"host",
+        ipaddress.IPv4Address("10.0.0.1"),
+        ".1.3.6.1.4.1.8072.4",
+    ]
+
+    v1 = decoded.to_v1()
+    assert v1.version == SnmpVersion.v1
+    assert v1.encode() == SnmpV1TrapMessage(SnmpVersion.v1, "public", pdu).encode()
+
+
+@pytest.mark.parametrize(
+    ("trap_oid", "enterprise", "generic_trap", "specific_trap"),
+    (
+        (".1.3.6.1.6.3.1.1.5.4", ".1.3.6.1.6.3.1.1.5", 3, 0),
+        (".1.3.6.1.4.1.8072.4.0.17", ".1.3.6.1.4.1.8072.4", 6, 17),
+        (".1.3.6.1.4.1.8072.4.10", ".1.3.6.1.4.1.8072.4", 6, 10),
+    ),
+)
+def test_v2_trap_to_v1(trap_oid: str, enterprise: str, generic_trap: int, specific_trap: int) -> None:
+    varbinds = [
+        SnmpVarbind(".1.3.6.1.2.1.1.3.0", 99, Number.TimeTicks),
+        SnmpVarbind(".1.3.6.1.6.3.1.1.4.1.0", trap_oid, Number.ObjectIdentifier),
+        SnmpVarbind(".1.3.6.1.2.1.2.2.1.1.3", 3),
+    ]
+    message = SnmpV2TrapMessage(SnmpVersion.v2c, "public", SnmpV2Trap(varbinds)).to_v1()
+    pdu = message.data
+    assert (pdu.enterprise, pdu.generic_trap, pdu.specific_trap) == (enterprise, generic_trap, specific_trap)
+    assert pdu.time_stamp == 99
+    assert pdu.agent_addr == ipaddress.IPv4Address("0.0.0.0")
+    assert pdu.varbinds == varbinds[2:]
+    assert pdu.trap_oid == (trap_oid if generic_trap != 6 else f"{enterprise}.0.{specific_trap}")
+
+
+@pytest.mark.parametrize(
+    "varbinds",
+    (
+        [SnmpVarbind(".1.3.6.1.6.3.1.1.4.1.0", ".1.3.6.1.6.3.1.1.5.1", Number.ObjectIdentifier)],
+        [
+            SnmpVarbind(".1.3.6.1.2.1.1.3.0", 99, Number.TimeTicks),
+            SnmpVarbind(".1.3.6.1.6.3.1.1.4.1.0", ".1.3.6.1.6.3.1.1.5.1", Number.ObjectIdentifier),
+            SnmpVarbind(".1.3.6.1.2.1.31.1.1.1.6.1", 2 ** 40, Number.Counter64),
+        ],
+    ),
+)
+def test_v2_trap_to_v1_error(varbinds: List[SnmpVarbind]) -> None:
+    with pytest.raises(ValueError):
+        SnmpV1TrapPDU.from_v2_varbinds(varbinds)
+
+
+def test_decode_many() -> None:
+    results = decode_many([_v1_trap(), b"\x30\x03\x02\x01", _v2_notification(PDUType.SNMPv2Trap)])
+    assert len(results) == 3
+    assert isinstance(results[0], SnmpV1TrapMessage)
+    assert isinstance(results[1], Error)
+    assert isinstance(results[2], SnmpV2TrapMessage)
+
+
+def test_decode_many_custom_decoder() -> None:
+    results = decode_many([_v2_notification(PDUType.GetResponse)], SnmpResponse.decode)
+    assert isinstance(results[0], SnmpResponse)
+    assert results[0].data.request_id == 42
+
+
+@pytest.mark.parametrize("workers", (1, 2, 4))
+def test_decode_many_parallel(workers: int) -> None:
+    datagrams = [_v1_trap(), b"\x30\x03\x02\x01", _v2_notification(PDUType.SNMPv2Trap)] * 5
+    results = decode_many_parallel(datagrams, workers=workers)
+    assert len(results) == len(datagrams)
+    for i, result in enumerate(results):
+        assert isinstance(result, (SnmpV1TrapMessage, Error, SnmpV2TrapMessage)[i % 3])
+    assert results[0].data.enterprise == ".1.3.6.1.4.1.8072.4"
+    # the error context survives the worker processes
+    error = decode_many(datagrams[1:2])[0]
+    assert error.offset is not None
+    assert (results[-2].offset, results[-2].tag, results[-2].depth) == (error.offset, error.tag, error.depth)
+
+
+def test_to_dict_v1_trap() -> None:
+    message = parse_trap(_v1_trap())
+    assert message is not None
+    assert json.loads(json.dumps(to_dict(message))) == {
+        "version": "v1",
+        "community": "public",
+        "pdu": {
+            "type": "SNMPv1Trap",
+            "enterprise": ".1.3.6.1.4.1.8072.4",
+            "agent_addr": "10.0.0.1",
+            "generic_trap": 6,
+            "specific_trap": 17,
+            "time_stamp": 1234,
+            "varbinds": [{"oid": ".1.3.6.1.2.1.1.5.0", "type": "OctetString", "value": "686f7374"}],
+        },
+    }
+
+
+def test_to_dict_v2_trap() -> None:
+    message = parse_trap(_v2_notification(PDUType.SNMPv2Trap))
+    assert message is not None
+    assert to_dict(message) == {
+        "version": "v2c",
+        "community": "public",
+        "pdu": {
+            "type": "SNMPv2Trap",
+            "request_id": 42,
+            "error_status": 0,
+            "error_index": 0,
+            "varbinds": [
+                {"oid": ".1.3.6.1.6.3.1.1.4.1.0", "type": "ObjectIdentifier", "value": ".1.3.6.1.6.3.1.1.5.1"}
+            ],
+        },
+    }
+
+
+def test_peek_request_id() -> None:
+    message = SnmpMessage(SnmpVersion.v2c, "public", GetRequest([SnmpVarbind(".1.3.6.1.2.1.1.5.0")]))
+    assert peek_request_id(message.encode()) == message.data.request_id
+    assert peek_request_id(_v2_notification(PDUType.InformRequest)) == 42
+
+
+def test_peek_request_id_v3() -> None:
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(SnmpVersion.v3, Number.Integer)
+        with encoder.enter(Number.Sequence):
+            encoder.write(1234, Number.Integer)
+            encoder.write(65507, Number.Integer)
+            encoder.write(b"\x04", Number.OctetString)
+            encoder.write(3, Number.Integer)
+        encoder.write(b"", Number.OctetString)
+    assert peek_request_id(encoder.output()) == 1234
+
+
+@pytest.mark.parametrize("data", (b"", b"\x30\x03\x02\x01\x01", b"\x30\x05\x02\x01\x01\x04\x00"))
+def test_peek_request_id_error(data: bytes) -> None:
+    with pytest.raises(Error):
+        peek_request_id(data)
+
+
+@pytest.mark.parametrize("pdu_type", (PDUType.SNMPv2Trap, PDUType.InformRequest, PDUType.GetResponse))
+def test_peek_pdu_type(pdu_type: PDUType) -> None:
+    assert peek_pdu_type(_v2_notification(pdu_type)) is pdu_type
+
+
+def test_pdu_type() -> None:
+    message = SnmpMessage(SnmpVersion.v2c, "public", GetBulkRequest([SnmpVarbind(".1.3.6.1.2.1.1")], 0, 10))
+    assert message.pdu_type is PDUType.GetBulkRequest
+    assert peek_pdu_type(message.encode()) is PDUType.GetBulkRequest
+    assert SnmpResponse.decode(_v2_notification(PDUType.GetResponse)).pdu_type is PDUType.GetResponse
+    trap = parse_trap(_v1_trap())
+    assert trap is not None and trap.pdu_type is PDUType.SNMPv1Trap
+    trap = parse_trap(_v2_notification(PDUType.InformRequest))
+    assert trap is not None and trap.pdu_type is PDUType.InformRequest
+
+
+@pytest.mark.parametrize("data", (b"\x30\x05\x02\x01\x03\x30\x00", b"\x30\x07\x02\x01\x01\x04\x00\x02\x00"))
+def test_peek_pdu_type_error(data: bytes) -> None:
+    with pytest.raises(Error):
+        peek_pdu_type(data)
+
+
+def test_request_id_generator() -> None:
+    ids = RequestIdGenerator(2_147_483_646)
+    assert [next(ids) for _ in range(4)] == [2_147_483_646, 2_147_483_647, 1, 2]
+    assert 1 <= next(RequestIdGenerator()) <= 2_147_483_647
+
+
+@pytest.mark.parametrize("start", (0, -1, 2_147_483_648))
+def test_request_id_generator_error(start: int) -> None:
+    with pytest.raises(ValueError):
+        RequestIdGenerator(start)
+
+
+def test_request_id_generator_threads() -> None:
+    ids = RequestIdGenerator(1)
+    with ThreadPoolExecutor(max_workers=4) as executor:
+        values = list(executor.map(lambda _: next(ids), range(1000)))
+    assert sorted(values) == list(range(1, 1001))
+
+
+def test_pdu_request_ids_are_distinct() -> None:
+    first, second = GetRequest([]), GetRequest([])
+    assert first.request_id != second.request_id
+
+
+@pytest.mark.parametrize(
+    ("pdu_type", "pdu_cls"), ((PDUType.GetRequest, GetRequest), (PDUType.GetNextRequest, GetNextRequest))
+)
+def test_encode_get(pdu_type: PDUType, pdu_cls: type) -> None:
+    oids = [".1.3.6.1.2.1.1.5.0", "1.3.6.1.2.1.1.1.0"]
+    pdu = pdu_cls([SnmpVarbind(oid) for oid in oids])
+    pdu.request_id = 1234
+    message = SnmpMessage(SnmpVersion.v2c, "public", pdu)
+    assert encode_get("public", 1234, oids, pdu_type=pdu_type) == message.encode()
+    assert encode_get(b"public", 1234, iter(oids), pdu_type=pdu_type) == message.encode()
+
+
+def test_encode_get_v1() -> None:
+    assert encode_get("public", 1, [], version=SnmpVersion.v1) == (
+        b"\x30\x18\x02\x01\x00\x04\x06public\xa0\x0b\x02\x01\x01\x02\x01\x00\x02\x01\x00\x30\x00"
+    )
+
+
+def test_encode_get_error() -> None:
+    with pytest.raises(ValueError):
+        encode_get("public", 1, [], pdu_type=PDUType.SetRequest)
+
+
+def test_encode_getbulk() -> None:
+    oids = [".1.3.6.1.2.1.1.5.0", "1.3.6.1.2.1.2.2.1.2"]
+    pdu = GetBulkRequest([SnmpVarbind(oid) for oid in oids], 1, 25)
+    pdu.request_id = 1234
+    message = SnmpMessage(SnmpVersion.v2c, "public", pdu)
+    assert encode_getbulk("public", 1234, 1, 25, oids) == message.encode()
+    assert encode_getbulk(b"public", 1234, 1, 25, iter(oids)) == message.encode()
+
+
+def test_encode_max_size() -> None:
+    oids = [f".1.3.6.1.2.1.2.2.1.{i}.1" for i in range(1, 23)]
+    size = len(encode_get("public", 1, oids))
+    assert len(encode_get("public", 1, oids, max_size=size)) == size
+    with pytest.raises(MessageTooBigError):
+        encode_get("public", 1, oids, max_size=size - 1)
+    with pytest.raises(MessageTooBigError):
+        encode_getbulk("public", 1, 0, 10, oids, max_size=size - 1)
+    message = SnmpMessage(SnmpVersion.v2c, "public", GetRequest([SnmpVarbind(oid) for oid in oids]))
+    with pytest.raises(MessageTooBigError):
+        message.encode(max_size=size - 1)
+
+
+def test_encode_getbulk_v1() -> None:
+    with pytest.raises(ValueError):
+        encode_getbulk("public", 1, 0, 10, [], version=SnmpVersion.v1)
+
+
+def _response(pdu_type: PDUType) -> bytes:
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(SnmpVersion.v2c, Number.Integer)
+        encoder.write("public", Number.OctetString)
+        with encoder.enter(pdu_type, Class.Context):
+            encoder.write(42, Number.Integer)
+            encoder.write(2, Number.Integer)
+            encoder.write(1, Number.Integer)
+            with encoder.enter(Number.Sequence):
+                with encoder.enter(Number.Sequence):
+                    encoder.write("1.3.6.1.2.1.1.5.0", Number.ObjectIdentifier)
+                    encoder.write("host")
+    return encoder.output()
+
+
+@pytest.mark.parametrize("pdu_type", (PDUType.GetResponse, PDUType.Report))
+def test_decode_response(pdu_type: PDUType) -> None:
+    response = decode_response(_response(pdu_type))
+    assert isinstance(response, Response)
+    assert response.version == SnmpVersion.v2c
+    assert response.community == "public"
+    assert response.pdu_type == pdu_type
+    assert response.request_id == 42
+    assert response.error_status == 2
+    assert response.error_index == 1
+    assert [(varbind.oid, varbind.value) for varbind in response.varbinds] == [(".1.3.6.1.2.1.1.5.0", b"host")]
+
+
+def test_decode_response_view_threshold() -> None:
+    # the community is copied even if it is longer than the threshold
+    response = decode_response(_response(PDUType.GetResponse), view_threshold=4)
+    assert response.community == "public"
+    value = response.varbinds[0].value
+    assert isinstance(value, OctetView)
+    assert value.to_bytes() == b"host"
+
+
+def test_decode_response_not_a_response() -> None:
+    with pytest.raises(Error):
+        decode_response(_v2_notification(PDUType.SNMPv2Trap))
+
+
+def test_decode_response_partial() -> None:
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(SnmpVersion.v2c, Number.Integer)
+        encoder.write("public", Number.OctetString)
+        with encoder.enter(PDUType.GetResponse, Class.Context):
+            encoder.write(42, Number.Integer)
+            encoder.write(0, Number.Integer)
+            encoder.write(0, Number.Integer)
+            with encoder.enter(Number.Sequence):
+                with encoder.enter(Number.Sequence):
+                    encoder.write("1.3.6.1.2.1.4.20.1.1.0", Number.ObjectIdentifier)
+                    encoder.write_raw(b"\x40\x03\x0a\x00\x00")
+                with encoder.enter(Number.Sequence):
+                    encoder.write("1.3.6.1.2.1.1.5.0", Number.ObjectIdentifier)
+                    encoder.write("host")
+    data = encoder.output()
+
+    with pytest.raises(Error):
+        decode_response(data)
+    response = decode_response(data, partial=True)
+    assert isinstance(response.varbinds[0].value, Malformed)
+    assert response.varbinds[1].value == b"host"
+    assert response.decode_errors == [response.varbinds[0].value.error]
+    assert decode_response(_response(PDUType.GetResponse), partial=True).decode_errors == []
+
+
+def _partial_response(malformed: bytes) -> bytes:
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(SnmpVersion.v2c, Number.Integer)
+        encoder.write("public", Number.OctetString)
+        with encoder.enter(PDUType.GetResponse, Class.Context):
+            encoder.write(42, Number.Integer)
+            encoder.write(0, Number.Integer)
+            encoder.write(0, Number.Integer)
+            with encoder.enter(Number.Sequence):
+                encoder.write_raw(malformed)
+                with encoder.enter(Number.Sequence):
+                    encoder.write("1.3.6.1.2.1.1.5.0", Number.ObjectIdentifier)
+                    encoder.write("host")
+    return encoder.output()
+
+
+@pytest.mark.parametrize(
+    ("malformed", "oids"),
+    (
+        # oid with a leading 0x80 arc octet
+        (b"\x30\x05\x06\x01\x80\x05\x00", [".1.3.6.1.2.1.1.5.0"]),
+        # varbind that is not a sequence
+        (b"\x04\x02ab", [".1.3.6.1.2.1.1.5.0"]),
+        # varbind sequence with a length beyond the varbind list, the rest of the list is lost
+        (b"\x30\x81", []),
+    ),
+)
+def test_decode_response_partial_varbind(malformed: bytes, oids: List[str]) -> None:
+    data = _partial_response(malformed)
+    with pytest.raises(Error):
+        decode_response(data)
+    response = decode_response(data, partial=True)
+    assert len(response.decode_errors) == 1
+    assert [varbind.oid for varbind in response.varbinds] == oids
+
+
+def test_decode_response_partial_header() -> None:
+    data = _response(PDUType.GetResponse).replace(b"\x02\x01\x2a", b"\x04\x01\x2a", 1)
+    with pytest.raises(Error):
+        decode_response(data, partial=True)
+
+
+@pytest.mark.parametrize("community", ("public", b"\xde\xad\xbe\xef"))
+def test_binary_community(community: Union[str, bytes]) -> None:
+    data = encode_get(community, 1, [".1.3.6.1.2.1.1.5.0"])
+    message = parse_message(data)
+    assert message.community == community
+    assert SnmpMessage(message.version, message.community, message.data).encode() == data
+
+    response = SnmpMessage(SnmpVersion.v2c, community, GetResponse([SnmpVarbind(".1.3.6.1.2.1.1.5.0", b"host")]))
+    assert decode_response(response.encode()).community == community
+    assert SnmpResponse.decode(response.encode()).community == community
+
+    trap = SnmpMessage(SnmpVersion.v2c, community, SnmpV2Trap([]))
+    decoded = parse_trap(trap.encode())
+    assert decoded is not None
+    assert decoded.community == community
+
+
+def test_binary_community_to_dict() -> None:
+    message = parse_message(encode_get(b"\xff\xfe", 1, []))
+    assert to_dict(message)["community"] == "fffe"
+
+
+def test_error_status_exception() -> None:
+    varbinds = [SnmpVarbind(".1.3.6.1.2.1.1.5.0"), SnmpVarbind(".1.3.6.1.2.1.1.6.0")]
+    assert error_status_exception(ErrorStatus.NoError, 0, varbinds) is None
+
+    exception = error_status_exception(ErrorStatus.NotWritable, 2, varbinds)
+    assert isinstance(exception, SnmpErrorNotWritable)
+    assert "oid: .1.3.6.1.2.1.1.6.0" in str(exception)
+
+    exception = error_status_exception(2, 0, varbinds)
+    assert isinstance(exception, SnmpErrorNoSuchName)
+    assert "oid" not in str(exception)
+
+
+@pytest.mark.parametrize(("error_status", "error_index"), ((99, 1), (ErrorStatus.GenErr, 3)))
+def test_error_status_exception_out_of_range(error_status: int, error_index: int) -> None:
+    exception = error_status_exception(error_status, error_index, [SnmpVarbind(".1.3.6.1.2.1.1.5.0")])
+    assert isinstance(exception, SnmpErrorStatus)
+
+
+@pytest.mark.parametrize(
+    ("base_oid", "oids", "expected"),
+    (
+        (".1.3.6.1.2.1.2", [".1.3.6.1.2.1.2.1.0", ".1.3.6.1.2.1.2.2.1.1.1"], -1),
+        (".1.3.6.1.2.1.2", [".1.3.6.1.2.1.2.1.0", ".1.3.6.1.2.1.3.1.0"], 1),
+        ("1.3.6.1.2.1.2", ["1.3.6.1.2.1.2.1.0", ".1.3.6.1.2.1.20.1.0"], 1),
+        (".1.3.6.1.2.1.2", [".1.3.6.1.2.1.2"], 0),
+        (".1.3.6.1.2.1.2", [], -1),
+    ),
+)
+def test_first_outside(base_oid: str, oids: List[str], expected: int) -> None:
+    assert first_outside(base_oid, oids) == expected
+
+
+def test_correlate_varbinds_get() -> None:
+    oids = ["1.3.6.1.2.1.1.1.0", ".1.3.6.1.2.1.1.3.0", ".1.3.6.1.2.1.1.5.0"]
+    varbinds = [SnmpVarbind(".1.3.6.1.2.1.1.1.0", b"linux"), SnmpVarbind(".1.3.6.1.2.1.1.4.0", b"root")]
+    assert correlate_varbinds(oids[:1], varbinds[:1]) == []
+    assert correlate_varbinds(oids, varbinds, PDUType.SetRequest) == [
+        VarbindMismatch(1, MismatchKind.WrongOid, ".1.3.6.1.2.1.1.3.0", ".1.3.6.1.2.1.1.4.0"),
+        VarbindMismatch(2, MismatchKind.Missing, ".1.3.6.1.2.1.1.5.0", None),
+    ]
+    assert correlate_varbinds([], varbinds) == [
+        VarbindMismatch(0, MismatchKind.Unexpected, None, ".1.3.6.1.2.1.1.1.0"),
+        VarbindMismatch(1, MismatchKind.Unexpected, None, ".1.3.6.1.2.1.1.4.0"),
+    ]
+
+
+def test_correlate_varbinds_get_next() -> None:
+    oids = [".1.3.6.1.2.1.1.9", ".1.3.6.1.2.1.2.2.1.10", ".1.3.6.1.2.1.1.1.0", ".1.3.6.1.6.3"]
+    varbinds = [
+        SnmpVarbind(".1.3.6.1.2.1.1.9.1.2.1", ".1.3.6.1.6.3.1", Number.ObjectIdentifier),
+        SnmpVarbind(".1.3.6.1.2.1.2.2.1.9.1", 0, Number.TimeTicks),
+        SnmpVarbind(".1.3.6.1.2.1.1.1.0", b"linux"),
+        SnmpVarbind(".1.3.6.1.6.3", None, Number.EndOfMibView),
+    ]
+    assert correlate_varbinds(oids, varbinds, PDUType.GetNextRequest) == [
+        VarbindMismatch(1, MismatchKind.NotIncreasing, ".1.3.6.1.2.1.2.2.1.10", ".1.3.6.1.2.1.2.2.1.9.1"),
+        VarbindMismatch(2, MismatchKind.NotIncreasing, ".1.3.6.1.2.1.1.1.0", ".1.3.6.1.2.1.1.1.0"),
+    ]
+
+    with pytest.raises(ValueError):
+        correlate_varbinds(oids, varbinds, PDUType.GetBulkRequest)
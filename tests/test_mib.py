@@ -0,0 +1,244 @@
+import pytest
+
+from aiosnmp.exceptions import SnmpMibError
+from aiosnmp.mib import Mib, OidResolver, parse_mib
+
+IF_MIB = """
+IF-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    MODULE-IDENTITY, OBJECT-TYPE, Counter32, Integer32, mib-2
+        FROM SNMPv2-SMI
+    TEXTUAL-CONVENTION, DisplayString, PhysAddress
+        FROM SNMPv2-TC;
+
+ifMIB MODULE-IDENTITY
+    LAST-UPDATED "200006140000Z"
+    ORGANIZATION "IETF Interfaces MIB Working Group"
+    CONTACT-INFO "-- not a comment"
+    DESCRIPTION
+            "The MIB module to describe generic objects for network
+            interface sub-layers."
+    REVISION      "200006140000Z"
+    DESCRIPTION   "Clarifications."
+    ::= { mib-2 31 }
+
+ifMIBObjects OBJECT IDENTIFIER ::= { ifMIB 1 }
+
+interfaces   OBJECT IDENTIFIER ::= { mib-2 2 }
+
+InterfaceIndex ::= TEXTUAL-CONVENTION
+    DISPLAY-HINT "d"
+    STATUS       current
+    DESCRIPTION  "A unique value, greater than zero, for each interface."
+    SYNTAX       Integer32 (1..2147483647)
+
+IfAdminStatus ::= TEXTUAL-CONVENTION
+    STATUS       current
+    DESCRIPTION  "Desired state."
+    SYNTAX       INTEGER { up(1), down(2), testing(3) }
+
+ifTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF IfEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "A list of interface entries."
+    ::= { interfaces 2 }
+
+ifEntry OBJECT-TYPE
+    SYNTAX      IfEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "An entry."
+    INDEX   { ifIndex }
+    ::= { ifTable 1 }
+
+IfEntry ::=
+    SEQUENCE {
+        ifIndex                 InterfaceIndex,
+        ifDescr                 DisplayString,
+        ifPhysAddress           PhysAddress,
+        ifAdminStatus           INTEGER,
+        ifInOctets              Counter32
+    }
+
+ifIndex OBJECT-TYPE
+    SYNTAX      InterfaceIndex
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "A unique value."
+    ::= { ifEntry 1 }
+
+ifDescr OBJECT-TYPE
+    SYNTAX      DisplayString (SIZE (0..255))
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "A textual string."
+    ::= { ifEntry 2 }
+
+ifAdminStatus OBJECT-TYPE
+    SYNTAX  INTEGER {
+                up(1),       -- ready to pass packets
+                down(2),
+                testing(3)   -- in some test mode
+            }
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "The desired state of the interface."
+    ::= { ifEntry 7 }
+
+ifInOctets OBJECT-TYPE
+    SYNTAX      Counter32
+    UNITS       "octets"
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The total number of octets received on the interface."
+    ::= { ifEntry 10 }
+
+ifOrphan OBJECT-TYPE
+    SYNTAX      OBJECT IDENTIFIER
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "Parent is not defined."
+    ::= { ifUnknown 1 }
+
+END
+"""
+
+
+def test_parse_mib() -> None:
+    (module,) = parse_mib(IF_MIB)
+    assert module.name == "IF-MIB"
+    assert module.imports["OBJECT-TYPE"] == "SNMPv2-SMI"
+    assert module.imports["DisplayString"] == "SNMPv2-TC"
+    assert list(module.objects) == [
+        "ifMIB",
+        "ifMIBObjects",
+        "interfaces",
+        "ifTable",
+        "ifEntry",
+        "ifIndex",
+        "ifDescr",
+        "ifAdminStatus",
+        "ifInOctets",
+        "ifOrphan",
+    ]
+
+    if_mib = module.objects["ifMIB"]
+    assert if_mib.macro == "MODULE-IDENTITY"
+    assert if_mib.description is not None and if_mib.description.startswith("The MIB module")
+
+    admin_status = module.objects["ifAdminStatus"]
+    assert admin_status.macro == "OBJECT-TYPE"
+    assert admin_status.oid_parts == ["ifEntry", 7]
+    assert admin_status.syntax == "INTEGER"
+    assert admin_status.named_values == {1: "up", 2: "down", 3: "testing"}
+    assert admin_status.access == "read-write"
+    assert admin_status.status == "current"
+
+    assert module.objects["ifTable"].syntax == "SEQUENCE OF IfEntry"
+    assert module.objects["ifEntry"].index == ["ifIndex"]
+    assert module.objects["ifDescr"].syntax == "DisplayString"
+    assert module.objects["ifInOctets"].units == "octets"
+    assert module.objects["ifOrphan"].syntax == "OBJECT IDENTIFIER"
+
+    index = module.textual_conventions["InterfaceIndex"]
+    assert index.syntax == "Integer32"
+    assert index.display_hint == "d"
+    assert module.textual_conventions["IfAdminStatus"].named_values == {1: "up", 2: "down", 3: "testing"}
+
+
+def test_mib_resolve() -> None:
+    mib = Mib()
+    mib.load(IF_MIB)
+    assert "ifInOctets" in mib
+    assert mib.oid("ifMIBObjects") == ".1.3.6.1.2.1.31.1"
+    assert mib.oid("ifInOctets") == ".1.3.6.1.2.1.2.2.1.10"
+    assert mib["ifTable"].oid == ".1.3.6.1.2.1.2.2"
+    assert mib.oid("enterprises") == ".1.3.6.1.4.1"
+    assert mib.textual_convention("InterfaceIndex") is not None
+    assert mib.get("ifOrphan") is not None and mib["ifOrphan"].oid is None
+    with pytest.raises(SnmpMibError):
+        mib.oid("ifOrphan")
+    with pytest.raises(SnmpMibError):
+        mib.oid("sysDescr")
+
+
+def test_mib_load_resolves_across_modules() -> None:
+    mib = Mib()
+    mib.load(
+        """
+        ACME-MIB DEFINITIONS ::= BEGIN
+        IMPORTS acme FROM ACME-SMI;
+        acmeObjects OBJECT IDENTIFIER ::= { acme 1 }
+        acmeTrap TRAP-TYPE
+            ENTERPRISE acme
+            VARIABLES { acmeObjects }
+            ::= 3
+        END
+        """
+    )
+    assert mib["acmeObjects"].oid is None
+    mib.load("ACME-SMI DEFINITIONS ::= BEGIN acme OBJECT IDENTIFIER ::= { iso org(3) dod(6) 1 4 1 9999 } END")
+    assert mib.oid("acmeObjects") == ".1.3.6.1.4.1.9999.1"
+    assert mib.oid("acmeTrap") == ".1.3.6.1.4.1.9999.0.3"
+
+
+def test_mib_load_snmpv2_smi() -> None:
+    mib = Mib()
+    mib.load(
+        """
+        SNMPv2-SMI DEFINITIONS ::= BEGIN
+        org          OBJECT IDENTIFIER ::= { iso 3 }
+        dod          OBJECT IDENTIFIER ::= { org 6 }
+        internet     OBJECT IDENTIFIER ::= { iso(1) org(3) dod(6) 1 }
+        mgmt         OBJECT IDENTIFIER ::= { internet 2 }
+        mib-2        OBJECT IDENTIFIER ::= { mgmt 1 }
+        enterprises  OBJECT IDENTIFIER ::= { iso(1) org(3) dod(6) internet(1) private(4) 1 }
+        END
+        """
+    )
+    assert mib["internet"].oid == ".1.3.6.1"
+    assert mib["mgmt"].oid == ".1.3.6.1.2"
+    assert mib["mib-2"].oid == ".1.3.6.1.2.1"
+    assert mib["enterprises"].oid == ".1.3.6.1.4.1"
+    assert mib.oid("internet") == mib["internet"].oid
+
+
+@pytest.mark.parametrize(
+    "text",
+    (
+        "BROKEN-MIB DEFINITIONS ::= BEGIN",
+        "BROKEN-MIB ::= BEGIN END",
+        "BROKEN-MIB DEFINITIONS ::= BEGIN foo OBJECT IDENTIFIER ::= { iso 1 END",
+        "BROKEN-MIB DEFINITIONS ::= BEGIN # END",
+        "BROKEN-MIB DEFINITIONS ::= BEGIN foo OBJECT IDENTIFIER ::= { iso org(three) 1 } END",
+    ),
+)
+def test_parse_mib_error(text: str) -> None:
+    with pytest.raises(SnmpMibError):
+        parse_mib(text)
+
+
+def test_oid_resolver() -> None:
+    mib = Mib()
+    mib.load(IF_MIB)
+    resolver = OidResolver.from_mib(mib)
+    assert resolver.resolve(".1.3.6.1.2.1.2.2.1.10.3") == "ifInOctets.3"
+    assert resolver.resolve("1.3.6.1.2.1.2.2.1.10") == "ifInOctets"
+    assert resolver.resolve(".1.3.6.1.2.1.2.2.1.99.1") == "ifEntry.99.1"
+    assert resolver.resolve(".1.3.6.1.4.1.9") == ".1.3.6.1.4.1.9"
+    assert resolver.oid("ifInOctets.3") == ".1.3.6.1.2.1.2.2.1.10.3"
+    assert resolver.oid("ifTable") == ".1.3.6.1.2.1.2.2"
+    with pytest.raises(SnmpMibError):
+        resolver.oid("ifOrphan")
+
+
+def test_oid_resolver_mapping() -> None:
+    resolver = OidResolver({"sysDescr": "1.3.6.1.2.1.1.1", "system": ".1.3.6.1.2.1.1"})
+    assert len(resolver) == 2
+    assert resolver.resolve(".1.3.6.1.2.1.1.1.0") == "sysDescr.0"
+    assert resolver.resolve(".1.3.6.1.2.1.1.5.0") == "system.5.0"
+    resolver.update({"sysName": ".1.3.6.1.2.1.1.5"})
+    assert resolver.resolve(".1.3.6.1.2.1.1.5.0") == "sysName.0"
+    assert resolver.oid("sysDescr.0") == ".1.3.6.1.2.1.1.1.0"
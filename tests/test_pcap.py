@@ -0,0 +1,174 @@
+import ipaddress
+import os
+import struct
+import tempfile
+from typing import List
+
+import pytest
+
+from aiosnmp.asn1 import Error
+from aiosnmp.message import GetBulkRequest, GetRequest, SnmpMessage, SnmpVarbind, SnmpVersion, encode_get
+from aiosnmp.pcap import LinkType, iter_frames, read_pcap, udp_payload
+
+
+def _udp(source_port: int, destination_port: int, payload: bytes) -> bytes:
+    return struct.pack("!HHHH", source_port, destination_port, 8 + len(payload), 0) + payload
+
+
+def _ipv4(datagram: bytes) -> bytes:
+    header = struct.pack(
+        "!BBHHHBBH4s4s",
+        0x45,
+        0,
+        20 + len(datagram),
+        1,
+        0,
+        64,
+        17,
+        0,
+        ipaddress.IPv4Address("10.0.0.1").packed,
+        ipaddress.IPv4Address("10.0.0.2").packed,
+    )
+    return header + datagram
+
+
+def _ipv6(datagram: bytes) -> bytes:
+    header = struct.pack(
+        "!IHBB16s16s",
+        0x60000000,
+        len(datagram),
+        17,
+        64,
+        ipaddress.IPv6Address("2001:db8::1").packed,
+        ipaddress.IPv6Address("2001:db8::2").packed,
+    )
+    return header + datagram
+
+
+def _ethernet(packet: bytes, ethertype: int = 0x0800) -> bytes:
+    return b"\x00\x11\x22\x33\x44\x55" * 2 + struct.pack("!H", ethertype) + packet
+
+
+def _pcap(frames: List[bytes], link_type: int = LinkType.Ethernet) -> bytes:
+    data = struct.pack("<IHHiIII", 0xA1B2C3D4, 2, 4, 0, 0, 65535, link_type)
+    for i, frame in enumerate(frames):
+        data += struct.pack("<IIII", 1600000000 + i, 500000, len(frame), len(frame)) + frame
+    return data
+
+
+def _pcapng_block(block_type: int, body: bytes) -> bytes:
+    body += b"\x00" * (-len(body) % 4)
+    return struct.pack("<II", block_type, 12 + len(body)) + body + struct.pack("<I", 12 + len(body))
+
+
+def _pcapng(frames: List[bytes]) -> bytes:
+    data = _pcapng_block(0x0A0D0D0A, struct.pack("<IHHq", 0x1A2B3C4D, 1, 0, -1))
+    # if_tsresol = 10^-3
+    options = struct.pack("<HHB3x", 9, 1, 3) + struct.pack("<HH", 0, 0)
+    data += _pcapng_block(1, struct.pack("<HHI", LinkType.Raw, 0, 65535) + options)
+    for i, frame in enumerate(frames):
+        timestamp = 1600000000000 + i
+        header = struct.pack("<IIIII", 0, timestamp >> 32, timestamp & 0xFFFFFFFF, len(frame), len(frame))
+        data += _pcapng_block(6, header + frame)
+    return data
+
+
+def _get() -> bytes:
+    return encode_get("public", 42, [".1.3.6.1.2.1.1.1.0"])
+
+
+def test_read_pcap() -> None:
+    frames = [
+        _ethernet(_ipv4(_udp(50000, 161, _get()))),
+        _ethernet(_ipv4(_udp(50000, 53, b"dns"))),
+        _ethernet(_ipv4(_udp(50000, 162, b"\x30\x03\x02\x01"))),
+        _ethernet(b"\x00" * 28, ethertype=0x0806),
+    ]
+    records = read_pcap(_pcap(frames))
+    assert len(records) == 2
+    assert records[0].timestamp == 1600000000.5
+    assert records[0].source == ("10.0.0.1", 50000)
+    assert records[0].destination == ("10.0.0.2", 161)
+    assert isinstance(records[0].message, SnmpMessage)
+    assert isinstance(records[0].message.data, GetRequest)
+    assert records[0].message.data.request_id == 42
+    assert records[0].message.data.varbinds[0].oid == ".1.3.6.1.2.1.1.1.0"
+    assert isinstance(records[1].message, Error)
+
+
+def test_read_pcap_file() -> None:
+    with tempfile.TemporaryDirectory() as directory:
+        path = os.path.join(directory, "capture.pcap")
+        with open(path, "wb") as f:
+            f.write(_pcap([_ethernet(_ipv4(_udp(161, 50000, _get())))]))
+        records = read_pcap(path)
+    assert len(records) == 1
+    assert records[0].source == ("10.0.0.1", 161)
+
+
+def test_read_pcapng() -> None:
+    records = read_pcap(_pcapng([_ipv6(_udp(50000, 161, _get())), _ipv4(_udp(50000, 161, _get()))]))
+    assert len(records) == 2
+    assert round(records[0].timestamp, 3) == 1600000000.0
+    assert round(records[1].timestamp, 3) == 1600000000.001
+    assert records[0].source == ("2001:db8::1", 50000)
+    assert records[1].source == ("10.0.0.1", 50000)
+
+
+def test_read_pcap_ports() -> None:
+    records = read_pcap(_pcap([_ethernet(_ipv4(_udp(50000, 1161, _get())))]), ports=[1161], decode=bytes)
+    assert len(records) == 1
+    assert records[0].message == _get()
+
+
+def test_read_pcap_get_bulk() -> None:
+    message = SnmpMessage(SnmpVersion.v2c, "public", GetBulkRequest([SnmpVarbind(".1.3.6.1")], 1, 10))
+    records = read_pcap(_pcap([_ethernet(_ipv4(_udp(50000, 161, message.encode())))]))
+    assert isinstance(records[0].message.data, GetBulkRequest)
+    assert records[0].message.data.non_repeaters == 1
+    assert records[0].message.data.max_repetitions == 10
+
+
+def test_iter_frames_invalid() -> None:
+    with pytest.raises(ValueError):
+        iter_frames(b"\x00" * 24)
+
+
+def test_read_pcapng_unknown_interface() -> None:
+    frame = _ipv4(_udp(50000, 161, _get()))
+    data = _pcapng_block(0x0A0D0D0A, struct.pack("<IHHq", 0x1A2B3C4D, 1, 0, -1))
+    data += _pcapng_block(6, struct.pack("<IIIII", 0, 0, 0, len(frame), len(frame)) + frame)
+    with pytest.raises(ValueError, match="interface 0"):
+        read_pcap(data)
+
+
+@pytest.mark.parametrize(
+    "frame, link_type",
+    (
+        (_ethernet(_ipv4(_udp(1, 161, b"x"))), LinkType.Ethernet),
+        (_ethernet(b"\x00\x05\x08\x00" + _ipv4(_udp(1, 161, b"x")), ethertype=0x8100), LinkType.Ethernet),
+        (_ethernet(_ipv6(_udp(1, 161, b"x")), ethertype=0x86DD), LinkType.Ethernet),
+        (b"\x02\x00\x00\x00" + _ipv4(_udp(1, 161, b"x")), LinkType.Null),
+        (_ipv4(_udp(1, 161, b"x")), LinkType.Raw),
+        (_ipv6(_udp(1, 161, b"x")), LinkType.IPv6),
+        (b"\x00" * 14 + b"\x08\x00" + _ipv4(_udp(1, 161, b"x")), LinkType.LinuxSLL),
+        (b"\x86\xdd" + b"\x00" * 18 + _ipv6(_udp(1, 161, b"x")), LinkType.LinuxSLL2),
+    ),
+)
+def test_udp_payload(frame: bytes, link_type: int) -> None:
+    udp = udp_payload(frame, link_type)
+    assert udp is not None
+    assert udp[1][1] == 161
+    assert udp[2] == b"x"
+
+
+@pytest.mark.parametrize(
+    "frame, link_type",
+    (
+        (b"", LinkType.Ethernet),
+        (_ethernet(_ipv4(_udp(1, 161, b"x"))[:10]), LinkType.Ethernet),
+        (_ipv4(_udp(1, 161, b"x")), 12345),
+    ),
+)
+def test_udp_payload_not_udp(frame: bytes, link_type: int) -> None:
+    assert udp_payload(frame, link_type) is None
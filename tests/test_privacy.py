@@ -0,0 +1,200 @@
+import pytest
+
+from aiosnmp.exceptions import SnmpPrivacyParametersReused
+from aiosnmp.privacy import (
+    KeyCache,
+    LocalizedKeys,
+    PrivParamsManager,
+    PrivProtocol,
+    SaltGenerator,
+    aes_iv,
+    des3_decrypt,
+    des3_encrypt,
+    des3_localize_key,
+    des_iv,
+    localize_key,
+    password_to_key,
+)
+
+ENGINE_ID = bytes.fromhex("000000000000000000000002")
+
+
+def test_salt_generator() -> None:
+    salts = SaltGenerator()
+    first = salts.next(b"engine-a")
+    assert salts.next(b"engine-a") == (first + 1) & 0xFFFFFFFFFFFFFFFF
+    assert 0 <= salts.next(b"engine-b") <= 0xFFFFFFFFFFFFFFFF
+
+
+def test_salt_generator_wraps() -> None:
+    salts = SaltGenerator()
+    salts._counters[b"engine"] = 0xFFFFFFFFFFFFFFFF
+    assert salts.next(b"engine") == 0xFFFFFFFFFFFFFFFF
+    assert salts.next(b"engine") == 0
+
+
+def test_privacy_parameters() -> None:
+    salts = SaltGenerator()
+    salts._counters[b"engine"] = 0x0102030405060708
+    assert salts.des_parameters(b"engine", 3) == b"\x00\x00\x00\x03\x05\x06\x07\x08"
+    assert salts.aes_parameters(b"engine") == b"\x01\x02\x03\x04\x05\x06\x07\x09"
+
+
+def test_salt_generator_reuse() -> None:
+    salts = SaltGenerator()
+    salts._counters[b"engine"] = 4
+    salts._issued[(b"engine", 32)] = (3, 0xFFFFFFFF)
+    assert salts.des_parameters(b"engine", 3)[4:] == b"\x00\x00\x00\x04"
+    with pytest.raises(SnmpPrivacyParametersReused):
+        salts.des_parameters(b"engine", 3)
+    # a reboot of the engine makes the salts unique again
+    assert salts.des_parameters(b"engine", 4) == b"\x00\x00\x00\x04\x00\x00\x00\x05"
+
+    salts.forget(b"engine")
+    assert salts._counters == {} and salts._issued == {}
+
+
+def test_priv_params_manager() -> None:
+    manager = PrivParamsManager()
+    manager.salts._counters[b"engine"] = 0x01020304FFFFFFFF
+    assert manager.parameters(b"engine", PrivProtocol.DES, 3) == b"\x00\x00\x00\x03\xff\xff\xff\xff"
+    assert manager.parameters(b"engine", PrivProtocol.DES, 3) == b"\x00\x00\x00\x03\x00\x00\x00\x00"
+    assert manager.parameters(b"engine", PrivProtocol.AES, 3) == b"\x01\x02\x03\x05\x00\x00\x00\x01"
+    assert len(manager.parameters(b"other", PrivProtocol.DES3, 1)) == 8
+
+
+def test_priv_params_manager_reuse() -> None:
+    salts = SaltGenerator()
+    manager = PrivParamsManager(salts)
+    salts._issued[(b"engine", 64)] = (None, 1 << 64)
+    with pytest.raises(SnmpPrivacyParametersReused):
+        manager.parameters(b"engine", PrivProtocol.AES)
+    manager.forget(b"engine")
+    assert len(manager.parameters(b"engine", PrivProtocol.AES)) == 8
+
+
+def test_des_iv() -> None:
+    key = bytes(8) + b"\xff" * 8
+    assert des_iv(key, b"\x00\x00\x00\x03\x05\x06\x07\x08") == b"\xff\xff\xff\xfc\xfa\xf9\xf8\xf7"
+
+
+def test_aes_iv() -> None:
+    assert aes_iv(3, 258, b"saltsalt") == b"\x00\x00\x00\x03\x00\x00\x01\x02saltsalt"
+
+
+@pytest.mark.parametrize(("key", "parameters"), ((bytes(8), bytes(8)), (bytes(16), bytes(4))))
+def test_des_iv_error(key: bytes, parameters: bytes) -> None:
+    with pytest.raises(ValueError):
+        des_iv(key, parameters)
+
+
+def test_aes_iv_error() -> None:
+    with pytest.raises(ValueError):
+        aes_iv(1, 1, bytes(7))
+
+
+@pytest.mark.parametrize(
+    ("hash_name", "key", "localized"),
+    (
+        ("md5", "9faf3283884e92834ebc9847d8edd963", "526f5eed9fcce26f8964c2930787d82b"),
+        ("sha1", "9fb5cc0381497b3793528939ff788d5d79145211", "6695febc9288e36282235fc7151f128497b38f3f"),
+    ),
+)
+def test_password_to_key(hash_name: str, key: str, localized: str) -> None:
+    # RFC 3414 A.3
+    assert password_to_key(b"maplesyrup", hash_name).hex() == key
+    assert localize_key(bytes.fromhex(key), ENGINE_ID, hash_name).hex() == localized
+
+
+def test_password_to_key_error() -> None:
+    with pytest.raises(ValueError):
+        password_to_key(b"", "md5")
+
+
+@pytest.mark.parametrize("hash_name", ("md5", "sha1", "sha256"))
+def test_des3_localize_key(hash_name: str) -> None:
+    key = password_to_key(b"maplesyrup", hash_name)
+    localized = des3_localize_key(key, ENGINE_ID, hash_name)
+    assert len(localized) == 32
+    assert localized.startswith(localize_key(key, ENGINE_ID, hash_name)[:32])
+
+
+def test_des3_localize_key_known_answer() -> None:
+    # known answer of the draft-reeder-snmpv3-usm-3desede key extension
+    key = password_to_key(b"maplesyrup", "md5")
+    localized = des3_localize_key(key, ENGINE_ID, "md5")
+    assert localized.hex() == "526f5eed9fcce26f8964c2930787d82b79eff44a90650ee0a3a40abfac5acc12"
+
+
+def test_des3_encrypt_decrypt() -> None:
+    key = des3_localize_key(password_to_key(b"maplesyrup", "md5"), ENGINE_ID, "md5")
+    parameters = b"\x00\x00\x00\x01\x00\x00\x00\x02"
+    encrypted = des3_encrypt(key, parameters, b"\x30\x03\x02\x01\x05")
+    assert len(encrypted) == 8
+    assert des3_decrypt(key, parameters, encrypted) == b"\x30\x03\x02\x01\x05" + bytes(3)
+    assert des3_decrypt(key, bytes(8), encrypted) != b"\x30\x03\x02\x01\x05" + bytes(3)
+
+
+@pytest.mark.parametrize(
+    ("key", "parameters", "data"), ((bytes(24), bytes(8), bytes(8)), (bytes(32), bytes(8), bytes(7)))
+)
+def test_des3_decrypt_error(key: bytes, parameters: bytes, data: bytes) -> None:
+    with pytest.raises(ValueError):
+        des3_decrypt(key, parameters, data)
+
+
+class FakeClock:
+    def __init__(self) -> None:
+        self.now = 1000.0
+
+    def __call__(self) -> float:
+        return self.now
+
+
+def test_key_cache_localize() -> None:
+    cache = KeyCache(clock=FakeClock())
+    keys = cache.localize(b"user", ENGINE_ID, "md5", b"maplesyrup", b"maplesyrup")
+    assert keys.auth_key.hex() == "526f5eed9fcce26f8964c2930787d82b"
+    assert keys.priv_key == keys.auth_key
+    assert cache.localize(b"user", ENGINE_ID, "md5", b"changed") is keys
+
+    keys = cache.localize(b"user", b"other", "md5", b"maplesyrup", b"maplesyrup", des3=True)
+    assert keys.priv_key is not None and len(keys.priv_key) == 32
+    assert len(cache) == 2
+
+
+def test_key_cache_ttl() -> None:
+    clock = FakeClock()
+    cache = KeyCache(ttl=60, clock=clock)
+    cache.put(b"user", b"engine", LocalizedKeys(b"auth", None))
+    clock.now += 59
+    assert cache.get(b"user", b"engine") == LocalizedKeys(b"auth", None)
+    clock.now += 1
+    assert cache.get(b"user", b"engine") is None
+    assert (b"user", b"engine") not in cache
+    assert len(cache) == 0
+
+
+def test_key_cache_len_expired() -> None:
+    clock = FakeClock()
+    cache = KeyCache(ttl=60, clock=clock)
+    cache.put(b"user", b"engine", LocalizedKeys(b"auth", None))
+    clock.now += 30
+    cache.put(b"other", b"engine", LocalizedKeys(b"auth", None))
+    assert len(cache) == 2
+    clock.now += 30
+    assert len(cache) == 1
+    assert (b"other", b"engine") in cache
+
+
+def test_key_cache_invalidate() -> None:
+    cache = KeyCache(clock=FakeClock())
+    for username in (b"a", b"b"):
+        for engine_id in (b"x", b"y"):
+            cache.put(username, engine_id, LocalizedKeys(username + engine_id, None))
+    cache.invalidate(engine_id=b"x")
+    assert sorted(cache._entries) == [(b"a", b"y"), (b"b", b"y")]
+    cache.invalidate(b"a", b"y")
+    assert sorted(cache._entries) == [(b"b", b"y")]
+    cache.invalidate()
+    assert len(cache) == 0
@@ -0,0 +1,44 @@
+import asyncio
+
+from aiosnmp.message import GetResponse, SnmpMessage, SnmpVarbind, SnmpVersion
+from aiosnmp.protocols import DuplicateCache, SnmpProtocol
+
+
+def test_duplicate_cache() -> None:
+    cache = DuplicateCache(maxsize=2)
+    assert cache.add(("host", 161, 1))
+    assert cache.add(("host", 161, 2))
+    assert not cache.add(("host", 161, 1))
+    assert cache.add(("host", 161, 3))
+    assert ("host", 161, 1) in cache
+    assert ("host", 161, 2) not in cache
+    assert len(cache) == 2
+
+    cache.discard(("host", 161, 1))
+    assert ("host", 161, 1) not in cache
+    cache.clear()
+    assert len(cache) == 0
+
+
+def test_duplicate_response_dropped() -> None:
+    loop = asyncio.new_event_loop()
+    try:
+        asyncio.set_event_loop(loop)
+        protocol = SnmpProtocol(timeout=1, retries=1)
+        pdu = GetResponse([SnmpVarbind(".1.3.6.1.2.1.1.5.0", b"host")])
+        pdu.request_id = 7
+        data = SnmpMessage(SnmpVersion.v2c, "public", pdu).encode()
+        key = ("127.0.0.1", 161, 7)
+        fut = loop.create_future()
+        protocol.requests[key] = fut
+
+        protocol.datagram_received(data, ("127.0.0.1", 161))
+        assert fut.result()[0].value == b"host"
+        assert key in protocol.answered
+
+        # a retransmitted response arriving before the request is cleaned up is ignored
+        protocol.datagram_received(data, ("127.0.0.1", 161))
+        assert protocol.requests[key] is fut
+    finally:
+        asyncio.set_event_loop(None)
+        loop.close()
@@ -0,0 +1,232 @@
+import datetime
+import ipaddress
+from typing import Any, Dict, List, Optional, Set, Type, Union
+
+import pytest
+
+from aiosnmp.asn1 import Decoder, Encoder, Number
+from aiosnmp.exceptions import SnmpErrorWrongLength, SnmpErrorWrongType, SnmpErrorWrongValue
+from aiosnmp.message import SnmpVarbind
+from aiosnmp.smi import (
+    IndexPart,
+    Syntax,
+    SyntaxRegistry,
+    TextualConvention,
+    apply_textual_conventions,
+    decode_bits,
+    decode_index,
+    encode_bits,
+    encode_index,
+    render,
+)
+
+
+@pytest.mark.parametrize(
+    ("value", "convention", "expected"),
+    (
+        (b"Linux host", TextualConvention.DisplayString, "Linux host"),
+        (b"\xff", TextualConvention.DisplayString, "�"),
+        (b"\x00\x1b\x21\xaa\xbb\xcc", TextualConvention.MacAddress, "00:1b:21:aa:bb:cc"),
+        (b"\x00\x1b", TextualConvention.MacAddress, b"\x00\x1b"),
+        (b"\x00\x1b\x21\xaa\xbb\xcc", TextualConvention.PhysAddress, "00:1b:21:aa:bb:cc"),
+        (b"\x02\x00\x1b\x21\xaa\xbb\xcc\xdd", TextualConvention.PhysAddress, "02:00:1b:21:aa:bb:cc:dd"),
+        (b"", TextualConvention.PhysAddress, ""),
+        (1, TextualConvention.TruthValue, True),
+        (2, TextualConvention.TruthValue, False),
+        (3, TextualConvention.TruthValue, 3),
+        (
+            b"\x07\xe4\x05\x1a\x0d\x1e\x0f\x03",
+            TextualConvention.DateAndTime,
+            datetime.datetime(2020, 5, 26, 13, 30, 15, 300_000),
+        ),
+        (
+            b"\x07\xe4\x05\x1a\x0d\x1e\x0f\x00-\x05\x1e",
+            TextualConvention.DateAndTime,
+            datetime.datetime(2020, 5, 26, 13, 30, 15, tzinfo=datetime.timezone(-datetime.timedelta(hours=5.5))),
+        ),
+        (b"\x07\xe4\x0d\x1a\x0d\x1e\x0f\x00", TextualConvention.DateAndTime, b"\x07\xe4\x0d\x1a\x0d\x1e\x0f\x00"),
+        (b"\x07\xe4", TextualConvention.DateAndTime, b"\x07\xe4"),
+    ),
+)
+def test_render(value: object, convention: TextualConvention, expected: object) -> None:
+    assert render(value, convention) == expected
+
+
+def test_apply_textual_conventions() -> None:
+    varbinds = [
+        SnmpVarbind(".1.3.6.1.2.1.1.1.0", b"Linux"),
+        SnmpVarbind(".1.3.6.1.2.1.2.2.1.2.1", b"eth0"),
+        SnmpVarbind(".1.3.6.1.2.1.2.2.1.6.1", b"\x00\x1b\x21\xaa\xbb\xcc"),
+        SnmpVarbind(".1.3.6.1.2.1.2.2.1.20.1", b"\x01"),
+        SnmpVarbind(".1.3.6.1.2.1.2.2.1.22", b"eth0"),
+    ]
+    apply_textual_conventions(
+        varbinds,
+        {
+            "1.3.6.1.2.1.1.1": TextualConvention.DisplayString,
+            ".1.3.6.1.2.1.2.2.1.2": TextualConvention.DisplayString,
+            ".1.3.6.1.2.1.2.2.1.6": TextualConvention.PhysAddress,
+        },
+    )
+    assert [varbind.value for varbind in varbinds] == ["Linux", "eth0", "00:1b:21:aa:bb:cc", b"\x01", b"eth0"]
+
+
+_NAMES = {0: "running", 1: "degraded", 9: "failed"}
+
+
+@pytest.mark.parametrize(
+    ("value", "names", "bits"),
+    (
+        (b"", None, set()),
+        (b"\x00", None, set()),
+        (b"\x80", None, {0}),
+        (b"\x41\x01", None, {1, 7, 15}),
+        (b"\xc0\x40", _NAMES, {"running", "degraded", "failed"}),
+        (b"\x20", _NAMES, {2}),
+    ),
+)
+def test_decode_bits(value: bytes, names: Optional[Dict[int, str]], bits: Set[Union[int, str]]) -> None:
+    assert decode_bits(value, names) == bits
+
+
+@pytest.mark.parametrize(
+    ("bits", "names", "value"),
+    (
+        ([], None, b""),
+        ([0], None, b"\x80"),
+        ([1, 7, 15, 7], None, b"\x41\x01"),
+        (["running", "failed", 2], _NAMES, b"\xa0\x40"),
+    ),
+)
+def test_encode_bits(bits: List[Union[int, str]], names: Optional[Dict[int, str]], value: bytes) -> None:
+    assert encode_bits(bits, names) == value
+
+
+@pytest.mark.parametrize("bits", (["unknown"], [-1]))
+def test_encode_bits_error(bits: List[Union[int, str]]) -> None:
+    with pytest.raises(ValueError):
+        encode_bits(bits, _NAMES)
+
+
+@pytest.mark.parametrize(
+    ("syntax", "value", "exception"),
+    (
+        (Syntax(ranges=[(1, 10), (20, 30)]), 5, None),
+        (Syntax(ranges=[(1, 10), (20, 30)]), 25, None),
+        (Syntax(ranges=[(1, 10), (20, 30)]), 15, SnmpErrorWrongValue),
+        (Syntax(ranges=[(1, 10)]), "5", SnmpErrorWrongType),
+        (Syntax(ranges=[(1, 10)]), True, SnmpErrorWrongType),
+        (Syntax(values=[1, 2]), 2, None),
+        (Syntax(values=[1, 2]), 3, SnmpErrorWrongValue),
+        (Syntax(Number.OctetString, sizes=[(0, 4)]), "host", None),
+        (Syntax(Number.OctetString, sizes=[(0, 4)]), "hosts", SnmpErrorWrongLength),
+        (Syntax(Number.OctetString, sizes=[(6, 6)]), b"\x00" * 6, None),
+        (Syntax(Number.OctetString, sizes=[(1, 1)]), "é", SnmpErrorWrongLength),
+        (Syntax(Number.OctetString), 1, SnmpErrorWrongType),
+        (Syntax(Number.IPAddress), ipaddress.IPv4Address("10.0.0.1"), None),
+        (Syntax(Number.IPAddress), "10.0.0.1", SnmpErrorWrongType),
+        (Syntax(Number.Gauge32, ranges=[(0, 100)]), 101, SnmpErrorWrongValue),
+    ),
+)
+def test_syntax_check(syntax: Syntax, value: Any, exception: Optional[Type[Exception]]) -> None:
+    if exception is None:
+        syntax.check(value, 1, ".1.3.6.1")
+    else:
+        with pytest.raises(exception):
+            syntax.check(value, 1, ".1.3.6.1")
+
+
+def test_syntax_unsupported() -> None:
+    with pytest.raises(ValueError):
+        Syntax(Number.Sequence)
+
+
+def test_syntax_registry() -> None:
+    registry = SyntaxRegistry({"1.3.6.1.2.1.2.2.1.7": Syntax(values=[1, 2, 3])})
+    registry.register(".1.3.6.1.2.1.1.5.0", Syntax(Number.OctetString, sizes=[(0, 255)]))
+    assert registry.get("1.3.6.1.2.1.2.2.1.7.12") is not None
+    assert registry.get(".1.3.6.1.2.1.2.2.1.8.12") is None
+
+    registry.validate([(".1.3.6.1.2.1.2.2.1.7.12", 2), ("1.3.6.1.2.1.1.5.0", "host"), (".1.3.6.1.2.1.1.6.0", 7)])
+    with pytest.raises(SnmpErrorWrongValue) as exc_info:
+        registry.validate([("1.3.6.1.2.1.1.5.0", "host"), (".1.3.6.1.2.1.2.2.1.7.12", 4)])
+    assert "index: 2, oid: .1.3.6.1.2.1.2.2.1.7.12" in str(exc_info.value)
+
+
+@pytest.mark.parametrize(
+    ("syntax", "value", "tag"),
+    (
+        (Syntax(Number.Gauge32), 5, 0x42),
+        (Syntax(Number.Counter32), 5, 0x41),
+        (Syntax(Number.TimeTicks), 5, 0x43),
+        (Syntax(Number.Integer), 5, 0x02),
+        (Syntax(Number.OctetString), "host", 0x04),
+        (Syntax(Number.ObjectIdentifier), ".1.3.6.1", 0x06),
+        (None, 5, 0x02),
+    ),
+)
+def test_syntax_registry_varbinds(syntax: Optional[Syntax], value: Any, tag: int) -> None:
+    registry = SyntaxRegistry({"1.3.6.1.2.1.2.2.1.5": syntax} if syntax is not None else {})
+    (varbind,) = registry.varbinds([(".1.3.6.1.2.1.2.2.1.5.1", value)])
+    encoder = Encoder()
+    varbind.encode(encoder)
+    decoder = Decoder(encoder.output())
+    with decoder.enter():
+        decoder.read()
+        assert decoder.peek().tag_byte == tag
+
+
+@pytest.mark.parametrize(
+    ("suffix", "parts", "values"),
+    (
+        (".12", [IndexPart()], [12]),
+        (
+            ".10.0.0.1.161",
+            [IndexPart(Number.IPAddress), IndexPart()],
+            [ipaddress.IPv4Address("10.0.0.1"), 161],
+        ),
+        (".4.104.111.115.116.3", [IndexPart(Number.OctetString), IndexPart()], [b"host", 3]),
+        (".104.111.115.116", [IndexPart(Number.OctetString, implied=True)], [b"host"]),
+        (".0.27.33.170.187.204", [IndexPart(Number.OctetString, size=6)], [b"\x00\x1b\x21\xaa\xbb\xcc"]),
+        (".3.1.3.6.7", [IndexPart(Number.ObjectIdentifier), IndexPart()], [".1.3.6", 7]),
+        (".1.3.6", [IndexPart(Number.ObjectIdentifier, implied=True)], [".1.3.6"]),
+        (".0", [IndexPart(Number.OctetString)], [b""]),
+    ),
+)
+def test_index(suffix: str, parts: List[IndexPart], values: List[Any]) -> None:
+    assert decode_index(suffix, parts) == values
+    assert decode_index([int(arc) for arc in suffix[1:].split(".")], parts) == values
+    assert encode_index(values, parts) == suffix
+
+
+def test_encode_index_str() -> None:
+    assert encode_index(["host", "10.0.0.1"], [IndexPart(Number.OctetString), IndexPart(Number.IPAddress)]) == (
+        ".4.104.111.115.116.10.0.0.1"
+    )
+
+
+@pytest.mark.parametrize(
+    ("suffix", "parts"),
+    (
+        (".1.2", [IndexPart()]),
+        (".10.0.0", [IndexPart(Number.IPAddress)]),
+        (".5.104.111", [IndexPart(Number.OctetString)]),
+        (".1.256", [IndexPart(Number.OctetString)]),
+        (".1.2", [IndexPart(Number.OctetString, implied=True), IndexPart()]),
+        ("", [IndexPart()]),
+    ),
+)
+def test_decode_index_invalid(suffix: str, parts: List[IndexPart]) -> None:
+    with pytest.raises(ValueError):
+        decode_index(suffix, parts)
+
+
+def test_encode_index_invalid() -> None:
+    with pytest.raises(ValueError):
+        encode_index([1, 2], [IndexPart()])
+    with pytest.raises(ValueError):
+        encode_index([b"\x00"], [IndexPart(Number.OctetString, size=6)])
+    with pytest.raises(ValueError):
+        encode_index([b"a", 1], [IndexPart(Number.OctetString, implied=True), IndexPart()])
+    with pytest.raises(ValueError):
+        IndexPart(Number.Boolean)
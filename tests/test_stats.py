@@ -0,0 +1,50 @@
+import pytest
+
+from aiosnmp.asn1 import Error
+from aiosnmp.message import (
+    GetRequest,
+    GetResponse,
+    SnmpMessage,
+    SnmpResponse,
+    SnmpVarbind,
+    SnmpVersion,
+    encode_get,
+    parse_message,
+)
+from aiosnmp.stats import enable_stats, reset_stats, stats
+
+
+def test_disabled_by_default() -> None:
+    reset_stats()
+    SnmpMessage(SnmpVersion.v2c, "public", GetRequest([SnmpVarbind("1.3.6.1")])).encode()
+    assert stats()["messages_encoded"] == 0
+
+
+def test_counts() -> None:
+    reset_stats()
+    enable_stats()
+    try:
+        request = encode_get("public", 1, [".1.3.6.1.2.1.1.1.0"])
+        response = SnmpMessage(
+            SnmpVersion.v2c, "public", GetResponse([SnmpVarbind("1.3.6.1.2.1.1.1.0", b"test")])
+        ).encode()
+        parse_message(request)
+        SnmpResponse.decode(response)
+        with pytest.raises(Error):
+            parse_message(b"\x30\x03\x02\x01")
+
+        assert stats() == {
+            "messages_decoded": 2,
+            "messages_encoded": 2,
+            "bytes_decoded": len(request) + len(response) + 4,
+            "bytes_encoded": len(request) + len(response),
+            "errors": {"PrematureEndError": 1},
+            "pdu_types": {"GetRequest": 2, "GetResponse": 2},
+        }
+
+        reset_stats()
+        assert stats()["messages_decoded"] == 0
+        assert stats()["errors"] == {}
+    finally:
+        enable_stats(False)
+        reset_stats()
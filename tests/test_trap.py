@@ -0,0 +1,72 @@
+import ipaddress
+import socket
+from typing import Optional
+
+import pytest
+
+from aiosnmp.asn1 import Error
+from aiosnmp.message import (
+    SnmpMessage,
+    SnmpV1TrapMessage,
+    SnmpV1TrapPDU,
+    SnmpV2Trap,
+    SnmpV2TrapMessage,
+    SnmpVarbind,
+    SnmpVersion,
+)
+from aiosnmp.trap import SnmpV2TrapServer, receive_many
+
+
+def _v1_trap() -> bytes:
+    pdu = SnmpV1TrapPDU(
+        ".1.3.6.1.4.1.8072.4",
+        ipaddress.IPv4Address("10.0.0.1"),
+        6,
+        17,
+        1234,
+        [SnmpVarbind(".1.3.6.1.2.1.1.5.0", b"host")],
+    )
+    return SnmpV1TrapMessage(SnmpVersion.v1, "public", pdu).encode()
+
+
+def _v2_trap() -> bytes:
+    pdu = SnmpV2Trap([SnmpVarbind(".1.3.6.1.2.1.1.3.0", 1234)])
+    pdu.request_id = 42
+    return SnmpMessage(SnmpVersion.v2c, "public", pdu).encode()
+
+
+def test_trap_server_communities() -> None:
+    server = SnmpV2TrapServer(communities=["public", b"private", b"\xff\xfe"])
+    assert server.communities == {"public", "private", b"\xff\xfe"}
+
+
+def test_receive_many() -> None:
+    server = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+    client = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+    with server, client:
+        server.bind(("127.0.0.1", 0))
+        server.setblocking(False)
+        client.bind(("127.0.0.1", 0))
+        datagrams = [_v1_trap(), b"\x30\x03\x02\x01", _v2_trap()]
+        for data in datagrams:
+            client.sendto(data, server.getsockname())
+
+        results = []
+        for _ in range(100):
+            results.extend(receive_many(server, max_count=2))
+            if len(results) == len(datagrams):
+                break
+        assert [addr for addr, _ in results] == [client.getsockname()] * 3
+        assert isinstance(results[0][1], SnmpV1TrapMessage)
+        assert isinstance(results[1][1], Error)
+        assert isinstance(results[2][1], SnmpV2TrapMessage)
+        assert receive_many(server) == []
+
+
+@pytest.mark.parametrize("timeout", (None, 1.0))
+def test_receive_many_blocking(timeout: Optional[float]) -> None:
+    with socket.socket(socket.AF_INET, socket.SOCK_DGRAM) as sock:
+        sock.bind(("127.0.0.1", 0))
+        sock.settimeout(timeout)
+        with pytest.raises(ValueError):
+            receive_many(sock)
@@ -0,0 +1,170 @@
+import hashlib
+import hmac
+
+import pytest
+
+from aiosnmp.asn1 import Asn1SyntaxError, Class, Decoder, Encoder, Error, Number
+from aiosnmp.engine import EngineTimeCache
+from aiosnmp.exceptions import SnmpInvalidEngineId, SnmpReport, SnmpReportNotInTimeWindow, SnmpReportUnknownEngineId
+from aiosnmp.message import GetRequest, PDUType, Report, SnmpResponse, SnmpVarbind, peek_request_id
+from aiosnmp.privacy import localize_key, password_to_key
+from aiosnmp.usm import (
+    Discovery,
+    ReportCounter,
+    classify_report,
+    encode_discovery_request,
+    parse_discovery_report,
+    reauthenticate,
+    report_exception,
+    verify_authentication,
+)
+
+ENGINE_ID = bytes.fromhex("80001f8880e9630000d61ff449")
+
+
+def test_decode_report_pdu() -> None:
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(1, Number.Integer)
+        encoder.write("public", Number.OctetString)
+        with encoder.enter(PDUType.Report, Class.Context):
+            encoder.write(42, Number.Integer)
+            encoder.write(0, Number.Integer)
+            encoder.write(0, Number.Integer)
+            with encoder.enter(Number.Sequence):
+                with encoder.enter(Number.Sequence):
+                    encoder.write("1.3.6.1.6.3.15.1.1.4.0", Number.ObjectIdentifier)
+                    encoder.write(7, Number.Integer)
+
+    message = SnmpResponse.decode(encoder.output())
+    assert isinstance(message.data, Report)
+    assert message.data.request_id == 42
+    assert classify_report(message.data.varbinds) is ReportCounter.UnknownEngineIDs
+
+
+@pytest.mark.parametrize(
+    ("oid", "exception"),
+    (
+        (".1.3.6.1.6.3.15.1.1.4.0", SnmpReportUnknownEngineId),
+        (".1.3.6.1.6.3.15.1.1.2.0", SnmpReportNotInTimeWindow),
+        (".1.3.6.1.4.1.8072.1", SnmpReport),
+    ),
+)
+def test_report_exception(oid: str, exception: type) -> None:
+    exc = report_exception([SnmpVarbind(oid, 3)])
+    assert type(exc) is exception
+    assert exc is not None and exc.oid == oid and exc.value == 3
+
+
+def test_report_exception_empty() -> None:
+    assert report_exception([]) is None
+    assert classify_report([SnmpVarbind(".1.3.6.1.2.1.1.1.0", 1)]) is None
+
+
+def _report(engine_id: bytes, *, version: int = 3, flags: bytes = b"\x00", pdu_type: int = PDUType.Report) -> bytes:
+    security_parameters = Encoder()
+    security_parameters.write_sequence([engine_id, 5, 1234, b"", b"", b""])
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(version, Number.Integer)
+        with encoder.enter(Number.Sequence):
+            encoder.write(7, Number.Integer)
+            encoder.write(65507, Number.Integer)
+            encoder.write(flags, Number.OctetString)
+            encoder.write(3, Number.Integer)
+        encoder.write(security_parameters.output(), Number.OctetString)
+        with encoder.enter(Number.Sequence):
+            encoder.write(engine_id, Number.OctetString)
+            encoder.write(b"", Number.OctetString)
+            with encoder.enter(pdu_type, Class.Context):
+                encoder.write(42, Number.Integer)
+                encoder.write(0, Number.Integer)
+                encoder.write(0, Number.Integer)
+                with encoder.enter(Number.Sequence):
+                    with encoder.enter(Number.Sequence):
+                        encoder.write("1.3.6.1.6.3.15.1.1.4.0", Number.ObjectIdentifier)
+                        encoder.write(1, Number.Counter32)
+    return encoder.output()
+
+
+def test_encode_discovery_request() -> None:
+    data = encode_discovery_request(7, 42)
+    assert peek_request_id(data) == 7
+    decoder = Decoder(data)
+    with decoder.enter():
+        assert decoder.read_integer() == 3
+        decoder.skip()
+        assert decoder.read_octet_string() == bytes.fromhex("300e0400020100020100040004000400")
+        with decoder.enter():
+            assert decoder.read_octet_string() == b""
+            assert decoder.read_octet_string() == b""
+            assert decoder.read_raw() == b"\xa0\x0b\x02\x01\x2a\x02\x01\x00\x02\x01\x00\x30\x00"
+
+
+def test_parse_discovery_report() -> None:
+    cache = EngineTimeCache()
+    discovery = parse_discovery_report(_report(ENGINE_ID), cache)
+    assert discovery == Discovery(ENGINE_ID, 5, 1234, 7, ReportCounter.UnknownEngineIDs)
+    assert cache.get(ENGINE_ID) == (5, 1234)
+
+
+@pytest.mark.parametrize(
+    ("data", "exception"),
+    (
+        (_report(ENGINE_ID, version=1), Asn1SyntaxError),
+        (_report(ENGINE_ID, flags=b"\x03"), Asn1SyntaxError),
+        (_report(ENGINE_ID, pdu_type=PDUType.GetResponse), Asn1SyntaxError),
+        (_report(b""), SnmpInvalidEngineId),
+    ),
+)
+def test_parse_discovery_report_error(data: bytes, exception: type) -> None:
+    with pytest.raises(exception):
+        parse_discovery_report(data)
+
+
+def _authenticated(auth_parameters: bytes, flags: bytes = b"\x05") -> bytes:
+    security_parameters = Encoder()
+    security_parameters.write_sequence([ENGINE_ID, 5, 1234, b"user", auth_parameters, b""])
+    encoder = Encoder()
+    with encoder.enter(Number.Sequence):
+        encoder.write(3, Number.Integer)
+        with encoder.enter(Number.Sequence):
+            encoder.write(7, Number.Integer)
+            encoder.write(65507, Number.Integer)
+            encoder.write(flags, Number.OctetString)
+            encoder.write(3, Number.Integer)
+        encoder.write(security_parameters.output(), Number.OctetString)
+        with encoder.enter(Number.Sequence):
+            encoder.write(ENGINE_ID, Number.OctetString)
+            encoder.write(b"", Number.OctetString)
+            pdu = GetRequest([SnmpVarbind(".1.3.6.1.2.1.1.5.0")])
+            pdu.request_id = 42
+            pdu.encode(encoder)
+    return encoder.output()
+
+
+@pytest.mark.parametrize(("hash_name", "length"), (("md5", 12), ("sha1", 12), ("sha256", 24), ("sha512", 48)))
+def test_reauthenticate(hash_name: str, length: int) -> None:
+    key = localize_key(password_to_key(b"maplesyrup", hash_name), ENGINE_ID, hash_name)
+    unsigned = _authenticated(bytes(length))
+    digest = hmac.new(key, unsigned, hash_name).digest()[:length]
+
+    signed = reauthenticate(_authenticated(b"\xff" * length), key, hash_name)
+    assert signed == _authenticated(digest)
+    assert verify_authentication(signed, key, hash_name)
+    assert not verify_authentication(signed, hashlib.new(hash_name, key).digest(), hash_name)
+    assert not verify_authentication(signed.replace(b"user", b"resu"), key, hash_name)
+
+
+@pytest.mark.parametrize(
+    "data",
+    (
+        _authenticated(bytes(12), flags=b"\x04"),
+        _authenticated(b""),
+        _authenticated(bytes(12))[:40],
+        _report(ENGINE_ID),
+    ),
+)
+def test_reauthenticate_error(data: bytes) -> None:
+    with pytest.raises((ValueError, Error)):
+        reauthenticate(data, bytes(16), "md5")